@@ -6,16 +6,41 @@ use rascii_art::RenderOptions;
 
 use serde_derive::Deserialize;
 
+use crate::export::ExportConfig;
+use crate::layout::LayoutConfig;
+use crate::modules::battery::BatteryConfig;
+use crate::modules::colors::ColorsConfig;
+use crate::modules::cpu::CpuConfig;
+use crate::modules::custom::CustomConfig;
+use crate::modules::disk::DiskConfig;
+use crate::modules::init::InitConfig;
+use crate::modules::load::LoadConfig;
+use crate::modules::locale::DateConfig;
+use crate::modules::network::NetworkConfig;
+use crate::modules::packages::PackagesConfig;
+use crate::modules::shell::ShellConfig;
+use crate::modules::terminal::TerminalConfig;
+use crate::system_info::{FORMAT_FIELDS, MODULE_NAMES};
 use crate::util::path_utils::get_path;
+use crate::util::template::{FormatConfig, Template};
+use crate::util::usage::UsageConfig;
 
 /// core struct to store data parsed from the configuration file
 ///
 /// NOTE: Notice how `ascii` and `image` are an [`Option`] i.e. one can provide
 /// either or None
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Default)]
 pub struct Config {
     pub ascii: Option<AsciiConfig>,
     pub image: Option<ImageConfig>,
+    #[serde(default)]
+    pub module: ModuleConfig,
+    #[serde(default)]
+    pub usage: UsageConfig,
+    #[serde(default)]
+    pub layout: LayoutConfig,
+    #[serde(default)]
+    pub export: ExportConfig,
 }
 
 impl Config {
@@ -64,6 +89,18 @@ impl Config {
             }
         };
 
+        if let Err(err) = config
+            .module
+            .validate_formats()
+            .and_then(|_| config.module.disk.validate())
+            .and_then(|_| config.module.colors.validate())
+            .and_then(|_| config.layout.validate(MODULE_NAMES))
+            .and_then(|_| config.export.validate())
+        {
+            eprintln!("Config error: {err}");
+            exit(1);
+        }
+
         match (&config.ascii, &config.image) {
             (Some(_), Some(_)) => {
                 eprintln!(
@@ -84,6 +121,125 @@ impl Config {
     }
 }
 
+/// store parsed information from the "\[module\]" table
+///
+/// every module can be configured through its own sub-table, e.g. `[module.disk]`
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct ModuleConfig {
+    #[serde(default)]
+    pub battery: BatteryConfig,
+    #[serde(default)]
+    pub boot: FormatConfig,
+    #[serde(default)]
+    pub colors: ColorsConfig,
+    #[serde(default)]
+    pub cpu: CpuConfig,
+    #[serde(default)]
+    pub custom: Vec<CustomConfig>,
+    #[serde(default)]
+    pub date: DateConfig,
+    #[serde(default)]
+    pub de: FormatConfig,
+    #[serde(default)]
+    pub disk: DiskConfig,
+    #[serde(default)]
+    pub host: FormatConfig,
+    #[serde(default)]
+    pub init: InitConfig,
+    #[serde(default)]
+    pub keyboard: FormatConfig,
+    #[serde(default)]
+    pub load: LoadConfig,
+    #[serde(default)]
+    pub locale: FormatConfig,
+    #[serde(default)]
+    pub memory: FormatConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub os: FormatConfig,
+    #[serde(default)]
+    pub packages: PackagesConfig,
+    #[serde(default)]
+    pub processes: FormatConfig,
+    #[serde(default)]
+    pub shell: ShellConfig,
+    #[serde(default)]
+    pub storage: FormatConfig,
+    #[serde(default)]
+    pub swap: FormatConfig,
+    #[serde(default)]
+    pub terminal: TerminalConfig,
+    #[serde(default)]
+    pub theme: FormatConfig,
+    #[serde(default)]
+    pub timezone: FormatConfig,
+    #[serde(default)]
+    pub uptime: FormatConfig,
+    #[serde(default)]
+    pub wm: FormatConfig,
+}
+
+impl ModuleConfig {
+    /// Returns the `format` string configured for `module`.
+    pub fn format(&self, module: &str) -> Option<&str> {
+        match module {
+            "battery" => self.battery.format.as_deref(),
+            "boot" => self.boot.format.as_deref(),
+            "cpu" => self.cpu.format.as_deref(),
+            "de" => self.de.format.as_deref(),
+            "disk" => self.disk.format.as_deref(),
+            "host" => self.host.format.as_deref(),
+            "init" => self.init.format.as_deref(),
+            "keyboard" => self.keyboard.format.as_deref(),
+            "load" => self.load.format.as_deref(),
+            "locale" => self.locale.format.as_deref(),
+            "memory" => self.memory.format.as_deref(),
+            "network" => self.network.format.as_deref(),
+            "os" => self.os.format.as_deref(),
+            "packages" => self.packages.format.as_deref(),
+            "processes" => self.processes.format.as_deref(),
+            "shell" => self.shell.format.as_deref(),
+            "storage" => self.storage.format.as_deref(),
+            "swap" => self.swap.format.as_deref(),
+            "terminal" => self.terminal.format.as_deref(),
+            "theme" => self.theme.format.as_deref(),
+            "timezone" => self.timezone.format.as_deref(),
+            "uptime" => self.uptime.format.as_deref(),
+            "wm" => self.wm.format.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Checks every `format` string for syntax errors and unknown placeholders.
+    ///
+    /// # Returns
+    /// * `Result<(), String>` - a message naming the offending table on error
+    ///
+    /// ```
+    /// use symfetch::config_handler::ModuleConfig;
+    ///
+    /// let config: ModuleConfig = toml::from_str(r#"
+    ///     [memory]
+    ///     format = "{used} of {totl}"
+    /// "#).unwrap();
+    /// assert_eq!(
+    ///     config.validate_formats().unwrap_err(),
+    ///     "[module.memory] format: unknown placeholder `{totl}`, expected one of used, total, percent (at position 10)"
+    /// );
+    /// ```
+    pub fn validate_formats(&self) -> Result<(), String> {
+        for (module, fields) in FORMAT_FIELDS {
+            if let Some(format) = self.format(module) {
+                Template::parse(format)
+                    .and_then(|template| template.validate(fields))
+                    .map_err(|err| format!("[module.{module}] format: {err}"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// store parsed information from the "\[ascii\]" table
 #[derive(Deserialize, Debug)]
 pub struct AsciiConfig {
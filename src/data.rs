@@ -16,9 +16,44 @@ use image_025 as image;
 use image::GenericImageView;
 
 use crate::config_handler::Config;
+use crate::layout::{self, LogoPosition};
 use crate::system_info::SystemInfo;
+use crate::util::ansi::{pad, truncate, visible_width};
 use crate::util::path_utils::get_path;
-use std::iter;
+use crate::util::term;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// images are not shrunk below this many columns to fit the terminal
+#[cfg(feature = "image")]
+const MIN_IMAGE_WIDTH: usize = 16;
+
+/// the composed output together with where the logo ended up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub lines: Vec<String>,
+    /// cells covered by the logo, `None` if there is no logo
+    pub logo: Option<LogoArea>,
+    /// image shown as the logo, set when the logo cells were left blank for exporters
+    pub image: Option<PathBuf>,
+}
+
+/// position and size of the logo in terminal cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogoArea {
+    pub column: usize,
+    pub row: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// the logo as read or rendered from the config, before fitting it to the terminal
+#[derive(Debug, Clone, Default)]
+pub struct Logo {
+    pub lines: Vec<String>,
+    /// whether the lines are an ANSI rendering of the configured image
+    pub image: bool,
+}
 
 /// holds information about config (+ system data)
 #[derive(Debug)]
@@ -34,26 +69,39 @@ impl Data {
 
     /// Returns ASCII art lines if configured
     pub fn ascii_lines(&self) -> Option<Vec<String>> {
-        self.config.ascii.as_ref().and_then(|ascii_config| {
-            std::fs::read_to_string(get_path(&ascii_config.path))
-                .ok()
-                .map(|content| {
-                    let lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
-                    let max_width = lines
-                        .iter()
-                        .map(|line| line.chars().count())
-                        .max()
-                        .unwrap_or(0);
-                    lines
-                        .into_iter()
-                        .map(|line| format!("{line:<max_width$}"))
-                        .collect()
-                })
-        })
+        self.config
+            .ascii
+            .as_ref()
+            .and_then(|ascii_config| read_ascii(&ascii_config.path))
     }
 
-    /// Main render function that handles all rendering logic with system info on the right side
+    /// Main render function that prints the logo and system info to the terminal
     pub fn render(&self) {
+        for line in self.frame(term::width(), false).lines {
+            println!("{line}");
+        }
+    }
+
+    /// Writes the output to `path`, the format is picked by the file extension.
+    pub fn export(&self, path: &Path) -> io::Result<()> {
+        crate::export::export(self, path)
+    }
+
+    /// Gathers the logo and system info and arranges them for a terminal of `term_width` columns.
+    ///
+    /// # Arguments
+    /// * `term_width` - width to fit the output into, `None` to not fit it at all
+    /// * `blank_image` - leave the cells of an image logo empty so it can be drawn separately
+    pub fn frame(&self, term_width: Option<usize>, blank_image: bool) -> Frame {
+        let system_info = SystemInfo::new(&self.config);
+        self.arrange(&self.logo(), &system_info, term_width, blank_image)
+    }
+
+    /// Reads the ASCII art or renders the image configured as logo.
+    pub fn logo(&self) -> Logo {
+        #[allow(unused_mut)]
+        let mut image_logo = false;
+        #[allow(unused_mut)]
         let mut left_side_lines = self.ascii_lines().unwrap_or_default();
 
         #[cfg(feature = "image-to-ascii")]
@@ -69,65 +117,117 @@ impl Data {
         #[allow(clippy::collapsible_if)]
         {
             if self.config.ascii.is_none() && left_side_lines.is_empty() {
-                if let Some(image_ansi_lines) = self.image_as_ansi_vec() {
+                if let Some(image_ansi_lines) = self.image_as_ansi_vec(None) {
                     left_side_lines.extend(image_ansi_lines);
+                    image_logo = true;
                 }
             }
         }
 
-        let system_info = SystemInfo::new();
+        Logo {
+            lines: left_side_lines,
+            image: image_logo,
+        }
+    }
+
+    /// Arranges already gathered parts for a terminal of `term_width` columns, see [`Data::frame`].
+    pub fn arrange(
+        &self,
+        logo: &Logo,
+        system_info: &SystemInfo,
+        term_width: Option<usize>,
+        blank_image: bool,
+    ) -> Frame {
+        let mut left_side_lines = logo.lines.clone();
+        #[allow(unused_mut)]
+        let mut image_logo = logo.image;
         let info_lines = system_info.as_vec();
 
-        if !left_side_lines.is_empty() {
-            self.render_side_by_side(&left_side_lines, &info_lines);
-        } else {
-            for line in info_lines {
-                println!("{line}");
+        let mut layout = self.config.layout.clone();
+        let mut left_width = self.left_width(&left_side_lines);
+
+        // try progressively more drastic ways of fitting into the terminal
+        if let Some(term_width) = term_width
+            && layout.fit.unwrap_or(true)
+            && !left_side_lines.is_empty()
+            && matches!(
+                layout.logo.unwrap_or_default(),
+                LogoPosition::Left | LogoPosition::Right
+            )
+        {
+            let fits = |width: usize, layout: &layout::LayoutConfig| {
+                layout.required_width(width, &info_lines) <= term_width
+            };
+
+            #[cfg(feature = "image")]
+            if !fits(left_width, &layout) && self.config.ascii.is_none() {
+                let available = term_width
+                    .saturating_sub(layout.required_width(0, &info_lines))
+                    .min(left_width);
+                if available >= MIN_IMAGE_WIDTH
+                    && let Some(lines) = self.image_as_ansi_vec(Some(available as u32))
+                {
+                    left_side_lines = lines;
+                    left_width = available;
+                }
+            }
+
+            if !fits(left_width, &layout)
+                && let Some(compact) = layout.compact_logo.as_deref().and_then(read_ascii)
+            {
+                left_width = widest(&compact);
+                left_side_lines = compact;
+                image_logo = false;
             }
-        }
-    }
 
-    /// Helper function to render content side-by-side with system info
-    fn render_side_by_side(&self, left_lines: &[String], info_lines: &[String]) {
-        let max_lines = left_lines.len().max(info_lines.len());
-
-        let empty = String::new();
-        let padded_left = left_lines
-            .iter()
-            .chain(iter::repeat(&empty))
-            .take(max_lines);
-        let padded_info = info_lines
-            .iter()
-            .chain(iter::repeat(&empty))
-            .take(max_lines);
-
-        let left_width = self.left_width(left_lines);
-
-        for (left_line, info_line) in padded_left.zip(padded_info) {
-            if left_line.is_empty() {
-                print!("{:<width$}", "", width = left_width);
-            } else {
-                print!("{left_line}");
+            if !fits(left_width, &layout) {
+                layout.logo = Some(LogoPosition::Top);
             }
-            print!(" | ");
-            println!("{info_line}");
+        }
+
+        let image = (blank_image && image_logo)
+            .then(|| {
+                self.config
+                    .image
+                    .as_ref()
+                    .map(|image| get_path(&image.path))
+            })
+            .flatten();
+        if image.is_some() {
+            left_side_lines = vec![" ".repeat(left_width); left_side_lines.len()];
+        }
+
+        let (lines, origin) = layout::compose(
+            &left_side_lines,
+            left_width,
+            &info_lines,
+            &layout,
+            term_width,
+        );
+        let lines = match term_width {
+            Some(term_width) if layout.fit.unwrap_or(true) => lines
+                .iter()
+                .map(|line| truncate(line, term_width))
+                .collect(),
+            _ => lines,
+        };
+        Frame {
+            lines,
+            logo: origin.map(|(column, row)| LogoArea {
+                column,
+                row,
+                width: left_width,
+                height: left_side_lines.len(),
+            }),
+            image,
         }
     }
 
     /// Helper to determine the width for left-side padding
     fn left_width(&self, left_lines: &[String]) -> usize {
-        if let Some(image_config) = &self.config.image {
-            image_config.width.unwrap_or_else(|| {
-                left_lines
-                    .first()
-                    .map(|line| line.chars().count() as u32)
-                    .unwrap_or(0)
-            }) as usize
-        } else {
-            left_lines
-                .first()
-                .map(|line| line.chars().count())
-                .unwrap_or(0)
+        match self.config.image.as_ref().and_then(|image| image.width) {
+            Some(width) => width as usize,
+            None => widest(left_lines),
         }
     }
 
@@ -153,8 +253,9 @@ impl Data {
         Some(rendered_ascii.lines().map(|l| l.to_string()).collect())
     }
 
+    /// Renders the configured image with ANSI colors, `width` overrides the configured width
     #[cfg(feature = "image")]
-    fn image_as_ansi_vec(&self) -> Option<Vec<String>> {
+    fn image_as_ansi_vec(&self, width: Option<u32>) -> Option<Vec<String>> {
         use termimage::ops;
         use termimage::{AnsiOutputFormat, Options};
 
@@ -162,7 +263,7 @@ impl Data {
         let image_path = get_path(&image_config.path);
         let image_path_str = image_path.to_string_lossy().to_string();
         let size = (
-            image_config.width.unwrap_or(40),
+            width.or(image_config.width).unwrap_or(40),
             image_config.height.unwrap_or(20),
         );
         let opts = Options {
@@ -181,3 +282,22 @@ impl Data {
         Some(rendered.lines().map(|l| l.trim_end().to_string()).collect())
     }
 }
+
+/// Returns the visible width of the widest line, images and colored art contain escape
+/// sequences that take up no space.
+fn widest(lines: &[String]) -> usize {
+    lines
+        .iter()
+        .map(|line| visible_width(line))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Reads ASCII art from `path`, padding every line to the width of the widest one
+fn read_ascii(path: &Path) -> Option<Vec<String>> {
+    std::fs::read_to_string(get_path(path)).ok().map(|content| {
+        let lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+        let max_width = widest(&lines);
+        lines.iter().map(|line| pad(line, max_width)).collect()
+    })
+}
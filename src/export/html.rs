@@ -0,0 +1,117 @@
+//! HTML export and conversion of ANSI colored text to HTML
+use std::fmt::Write;
+
+use super::{CHROME_BUTTONS, ExportConfig, FOREGROUND, escape, hex};
+use crate::data::Frame;
+use crate::util::ansi::{Style, spans};
+
+/// Converts lines containing ANSI escape sequences to HTML with inline styles.
+///
+/// Every styled run becomes a `<span>`, lines are separated by newlines so the result
+/// belongs in a `<pre>`. Escapes other than colors and text attributes are dropped.
+///
+/// ```
+/// use symfetch::export::html::ansi_to_html;
+///
+/// let html = ansi_to_html(&[
+///     "\x1b[1;33mOS:\x1b[0m Linux".to_string(),
+///     "\x1b[38;2;255;128;0;48;5;16m▀\x1b[0m <b>".to_string(),
+/// ]);
+/// assert_eq!(
+///     html,
+///     "<span style=\"color:#cdcd00;font-weight:bold\">OS:</span> Linux\n\
+///      <span style=\"color:#ff8000;background-color:#000000\">▀</span> &lt;b&gt;"
+/// );
+/// ```
+pub fn ansi_to_html(lines: &[String]) -> String {
+    let mut html = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            html.push('\n');
+        }
+        for span in spans(line) {
+            match css(&span.style) {
+                Some(css) => {
+                    let _ = write!(html, r#"<span style="{css}">{}</span>"#, escape(&span.text));
+                }
+                None => html.push_str(&escape(&span.text)),
+            }
+        }
+    }
+    html
+}
+
+/// inline CSS for `style`, `None` for unstyled text
+fn css(style: &Style) -> Option<String> {
+    let mut rules = Vec::new();
+    if let Some(fg) = style.fg {
+        rules.push(format!("color:{}", hex(fg)));
+    }
+    if let Some(bg) = style.bg {
+        rules.push(format!("background-color:{}", hex(bg)));
+    }
+    if style.bold {
+        rules.push("font-weight:bold".to_string());
+    }
+    if style.dim {
+        rules.push("opacity:0.6".to_string());
+    }
+    if style.italic {
+        rules.push("font-style:italic".to_string());
+    }
+    if style.underline {
+        rules.push("text-decoration:underline".to_string());
+    }
+    (!rules.is_empty()).then(|| rules.join(";"))
+}
+
+/// Renders `frame` as a standalone HTML page.
+///
+/// Only inline styles are used, so the `<pre>` can be embedded into other pages as is.
+///
+/// ```
+/// use symfetch::data::Frame;
+/// use symfetch::export::{html::render, ExportConfig};
+///
+/// let frame = Frame { lines: vec!["\x1b[36mroot\x1b[0m@host".to_string()], logo: None, image: None };
+/// let html = render(&frame, &ExportConfig::default());
+/// assert!(html.starts_with("<!DOCTYPE html>"));
+/// assert!(html.contains(r##"<span style="color:#00cdcd">root</span>@host"##));
+/// ```
+pub fn render(frame: &Frame, config: &ExportConfig) -> String {
+    let padding = config.padding();
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>symfetch</title>\n</head>\n");
+    let _ = writeln!(
+        html,
+        r#"<body style="margin:0;background-color:{}">"#,
+        hex(config.background())
+    );
+    let _ = writeln!(
+        html,
+        r#"<div style="display:inline-block;padding:{padding}px;background-color:{}">"#,
+        hex(config.background())
+    );
+    if config.chrome() {
+        let buttons: String = CHROME_BUTTONS
+            .iter()
+            .map(|color| {
+                format!(
+                    r#"<span style="display:inline-block;width:12px;height:12px;margin-right:8px;border-radius:50%;background-color:{}"></span>"#,
+                    hex(*color)
+                )
+            })
+            .collect();
+        let _ = writeln!(html, r#"<div style="margin-bottom:1em">{buttons}</div>"#);
+    }
+    let _ = writeln!(
+        html,
+        r#"<pre style="margin:0;font-family:'DejaVu Sans Mono',Menlo,Consolas,monospace;font-size:{}px;line-height:1.2;color:{}">{}</pre>"#,
+        config.font_size(),
+        hex(FOREGROUND),
+        ansi_to_html(&frame.lines)
+    );
+    html.push_str("</div>\n</body>\n</html>\n");
+    html
+}
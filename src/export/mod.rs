@@ -0,0 +1,182 @@
+//! writing the output to files instead of the terminal
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use colored::Color;
+use serde_derive::Deserialize;
+
+use crate::data::Data;
+use crate::util::ansi::{Rgb, rgb};
+use crate::util::path_utils::get_path;
+
+pub mod html;
+#[cfg(feature = "image")]
+pub mod png;
+pub mod svg;
+
+/// color used where the output doesn't set any
+pub const FOREGROUND: Rgb = (212, 212, 212);
+
+/// colors of the close, minimize and maximize buttons drawn by `chrome`
+pub const CHROME_BUTTONS: [Rgb; 3] = [(255, 95, 86), (255, 189, 46), (39, 201, 63)];
+
+/// DejaVu Sans Mono, drawn into PNG exports and embedded into SVG exports
+pub const BUNDLED_FONT: &[u8] = include_bytes!("../../assets/fonts/DejaVuSansMono.ttf");
+
+/// store parsed information from the "\[export\]" table
+///
+/// ```toml
+/// [export]
+/// # Optional: font = "~/.local/share/fonts/JetBrainsMono-Regular.ttf"
+/// # Optional: font_size = 16
+/// # Optional: background = "#1e1e1e"
+/// # Optional: padding = 24
+/// # Optional: chrome = true
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct ExportConfig {
+    /// TrueType font used for PNG exports, defaults to the bundled DejaVu Sans Mono
+    pub font: Option<PathBuf>,
+
+    /// font size in pixels, defaults to 14
+    pub font_size: Option<f32>,
+
+    /// color name or `#rrggbb`, defaults to `#1e1e1e`
+    pub background: Option<String>,
+
+    /// pixels around the text, defaults to 16
+    pub padding: Option<u32>,
+
+    /// draw a window title bar with buttons above the output
+    pub chrome: Option<bool>,
+}
+
+impl ExportConfig {
+    /// Checks that `background` is a color, `font_size` is positive and `font` exists.
+    ///
+    /// ```
+    /// use symfetch::export::ExportConfig;
+    ///
+    /// let config = ExportConfig { background: Some("#12345".to_string()), ..Default::default() };
+    /// assert_eq!(config.validate().unwrap_err(), "[export] background: unknown color `#12345`");
+    ///
+    /// let config = ExportConfig { font_size: Some(0.0), ..Default::default() };
+    /// assert_eq!(config.validate().unwrap_err(), "[export] font_size: expected a positive size, got 0");
+    /// ```
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(size) = self.font_size
+            && !(size > 0.0 && size.is_finite())
+        {
+            return Err(format!(
+                "[export] font_size: expected a positive size, got {size}"
+            ));
+        }
+        if let Some(font) = &self.font
+            && !get_path(font).is_file()
+        {
+            return Err(format!("[export] font: no such file `{}`", font.display()));
+        }
+        if let Some(color) = &self.background {
+            color
+                .parse::<Color>()
+                .map_err(|_| format!("[export] background: unknown color `{color}`"))?;
+        }
+        Ok(())
+    }
+
+    pub fn font_size(&self) -> f32 {
+        self.font_size.unwrap_or(14.0)
+    }
+
+    pub fn background(&self) -> Rgb {
+        self.background
+            .as_deref()
+            .and_then(|color| color.parse::<Color>().ok())
+            .map_or((30, 30, 30), rgb)
+    }
+
+    pub fn padding(&self) -> u32 {
+        self.padding.unwrap_or(16)
+    }
+
+    pub fn chrome(&self) -> bool {
+        self.chrome.unwrap_or(false)
+    }
+}
+
+/// Renders `data` into the format matching the extension of `path` and writes it there.
+///
+/// Colors are always kept, even if stdout is not a terminal. The output isn't fitted to
+/// the terminal either, the same config always exports the same layout.
+///
+/// HTML keeps image logos as colored blocks, the other formats draw the image itself.
+pub fn export(data: &Data, path: &Path) -> io::Result<()> {
+    colored::control::set_override(true);
+    let frame = |blank_image| data.frame(None, blank_image);
+    let config = &data.config.export;
+
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "svg" => fs::write(path, svg::render(&frame(true), config)),
+        "html" | "htm" => fs::write(path, html::render(&frame(false), config)),
+        #[cfg(feature = "image")]
+        "png" => png::render(&frame(true), config)?
+            .save(path)
+            .map_err(io::Error::other),
+        #[cfg(not(feature = "image"))]
+        "png" => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "PNG export needs the `image` feature",
+        )),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unsupported export format `{extension}`, expected svg, png or html"),
+        )),
+    }
+}
+
+/// Encodes the file at `path` as a `data:` URI.
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::export::data_uri;
+///
+/// let uri = data_uri(Path::new("tests/fixtures/root/proc/cmdline")).unwrap();
+/// assert!(uri.starts_with("data:application/octet-stream;base64,"));
+/// ```
+pub fn data_uri(path: &Path) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mime = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        _ => "application/octet-stream",
+    };
+    Ok(format!("data:{mime};base64,{}", STANDARD.encode(bytes)))
+}
+
+/// Formats a color as `#rrggbb`.
+pub fn hex((r, g, b): Rgb) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// Escapes the characters with a special meaning in XML and HTML.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,199 @@
+//! PNG export rasterizing the terminal output with a monospace font
+use std::fs;
+use std::io;
+
+use ab_glyph::{Font, FontArc, PxScale, ScaleFont, point};
+use image_025::imageops::{self, FilterType};
+use image_025::{Rgba, RgbaImage};
+
+use super::{BUNDLED_FONT, CHROME_BUTTONS, ExportConfig, FOREGROUND};
+use crate::data::Frame;
+use crate::util::ansi::{Rgb, spans, visible_width};
+use crate::util::path_utils::get_path;
+
+/// opacity of dim text
+const DIM: f32 = 0.6;
+
+/// size of a terminal cell and where the first one starts, in pixels
+struct Grid {
+    cell_width: f32,
+    line_height: f32,
+    ascent: f32,
+    left: f32,
+    top: f32,
+}
+
+impl Grid {
+    fn x(&self, column: usize) -> f32 {
+        self.left + column as f32 * self.cell_width
+    }
+
+    fn y(&self, row: usize) -> f32 {
+        self.top + row as f32 * self.line_height
+    }
+}
+
+/// Rasterizes `frame` cell by cell, the same grid the terminal uses.
+///
+/// Bold text is emboldened by drawing it twice, italics are drawn upright.
+///
+/// ```
+/// use symfetch::data::Frame;
+/// use symfetch::export::{png::render, ExportConfig};
+///
+/// let frame = Frame { lines: vec!["\x1b[41m  \x1b[0m ok".to_string()], logo: None, image: None };
+/// let config = ExportConfig { padding: Some(0), ..Default::default() };
+/// let image = render(&frame, &config).unwrap();
+/// assert_eq!(image.get_pixel(1, 1).0, [205, 0, 0, 255]);
+/// assert_eq!(image.get_pixel(image.width() - 1, 0).0, [30, 30, 30, 255]);
+/// ```
+pub fn render(frame: &Frame, config: &ExportConfig) -> io::Result<RgbaImage> {
+    let font = match &config.font {
+        Some(path) => FontArc::try_from_vec(fs::read(get_path(path))?),
+        // used unless `font` is set in the "[export]" table
+        None => FontArc::try_from_slice(BUNDLED_FONT),
+    }
+    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let scale = PxScale::from(config.font_size());
+    let scaled = font.as_scaled(scale);
+
+    let padding = config.padding() as f32;
+    let line_height = (scaled.ascent() - scaled.descent() + scaled.line_gap()).ceil();
+    // the title bar takes up two lines
+    let chrome_height = if config.chrome() {
+        2.0 * line_height
+    } else {
+        0.0
+    };
+    let grid = Grid {
+        cell_width: scaled.h_advance(font.glyph_id('M')),
+        line_height,
+        ascent: scaled.ascent(),
+        left: padding,
+        top: padding + chrome_height,
+    };
+
+    let columns = frame
+        .lines
+        .iter()
+        .map(|line| visible_width(line))
+        .max()
+        .unwrap_or(0);
+    let width = (grid.x(columns) + padding).ceil() as u32;
+    let height = (grid.y(frame.lines.len()) + padding).ceil() as u32;
+    let background = config.background();
+    let mut canvas = RgbaImage::from_pixel(width.max(1), height.max(1), rgba(background));
+
+    if config.chrome() {
+        let radius = line_height * 0.35;
+        for (i, color) in CHROME_BUTTONS.iter().enumerate() {
+            let cx = padding + line_height * (0.5 + 1.2 * i as f32);
+            let cy = padding + line_height * 0.5;
+            circle(&mut canvas, cx, cy, radius, *color);
+        }
+    }
+
+    for (row, line) in frame.lines.iter().enumerate() {
+        let top = grid.y(row);
+        let mut column = 0;
+        for span in spans(line) {
+            let cells = span.text.chars().count();
+            if let Some(bg) = span.style.bg {
+                fill(
+                    &mut canvas,
+                    grid.x(column),
+                    top,
+                    cells as f32 * grid.cell_width,
+                    grid.line_height,
+                    bg,
+                );
+            }
+
+            let fg = span.style.fg.unwrap_or(FOREGROUND);
+            let opacity = if span.style.dim { DIM } else { 1.0 };
+            for (i, c) in span.text.chars().enumerate() {
+                if c.is_whitespace() {
+                    continue;
+                }
+                let x = grid.x(column + i);
+                let strokes: &[f32] = if span.style.bold { &[0.0, 1.0] } else { &[0.0] };
+                for offset in strokes {
+                    let glyph = font
+                        .glyph_id(c)
+                        .with_scale_and_position(scale, point(x + offset, top + grid.ascent));
+                    if let Some(outlined) = font.outline_glyph(glyph) {
+                        let bounds = outlined.px_bounds();
+                        outlined.draw(|gx, gy, coverage| {
+                            let px = bounds.min.x as i64 + gx as i64;
+                            let py = bounds.min.y as i64 + gy as i64;
+                            blend(&mut canvas, px, py, fg, coverage * opacity);
+                        });
+                    }
+                }
+            }
+            if span.style.underline {
+                let baseline = top + grid.ascent + 2.0;
+                fill(
+                    &mut canvas,
+                    grid.x(column),
+                    baseline,
+                    cells as f32 * grid.cell_width,
+                    1.0,
+                    fg,
+                );
+            }
+            column += cells;
+        }
+    }
+
+    if let (Some(area), Some(path)) = (frame.logo, &frame.image)
+        && let Ok(image) = image_025::open(path)
+    {
+        let box_width = (area.width as f32 * grid.cell_width) as u32;
+        let box_height = (area.height as f32 * grid.line_height) as u32;
+        let image = image
+            .resize(box_width, box_height, FilterType::Triangle)
+            .to_rgba8();
+        let x = grid.x(area.column) as i64 + (box_width - image.width()) as i64 / 2;
+        let y = grid.y(area.row) as i64 + (box_height - image.height()) as i64 / 2;
+        imageops::overlay(&mut canvas, &image, x, y);
+    }
+
+    Ok(canvas)
+}
+
+fn rgba((r, g, b): Rgb) -> Rgba<u8> {
+    Rgba([r, g, b, 255])
+}
+
+/// Mixes `color` into the pixel at `x`, `y` by `alpha`, ignoring pixels outside the canvas.
+fn blend(canvas: &mut RgbaImage, x: i64, y: i64, (r, g, b): Rgb, alpha: f32) {
+    if x < 0 || y < 0 || x >= canvas.width() as i64 || y >= canvas.height() as i64 {
+        return;
+    }
+    let alpha = alpha.clamp(0.0, 1.0);
+    let pixel = canvas.get_pixel_mut(x as u32, y as u32);
+    for (channel, value) in pixel.0.iter_mut().zip([r, g, b]) {
+        *channel = (*channel as f32 * (1.0 - alpha) + value as f32 * alpha).round() as u8;
+    }
+}
+
+fn fill(canvas: &mut RgbaImage, x: f32, y: f32, width: f32, height: f32, color: Rgb) {
+    for py in y.round() as i64..(y + height).round() as i64 {
+        for px in x.round() as i64..(x + width).round() as i64 {
+            blend(canvas, px, py, color, 1.0);
+        }
+    }
+}
+
+/// Draws a filled circle with antialiased edges.
+fn circle(canvas: &mut RgbaImage, cx: f32, cy: f32, radius: f32, color: Rgb) {
+    let (left, right) = ((cx - radius).floor() as i64, (cx + radius).ceil() as i64);
+    let (top, bottom) = ((cy - radius).floor() as i64, (cy + radius).ceil() as i64);
+    for py in top..=bottom {
+        for px in left..=right {
+            let distance = ((px as f32 + 0.5 - cx).powi(2) + (py as f32 + 0.5 - cy).powi(2)).sqrt();
+            blend(canvas, px, py, color, radius - distance + 0.5);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! SVG export reproducing the terminal output cell by cell
+use std::fmt::Write;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use super::{BUNDLED_FONT, CHROME_BUTTONS, ExportConfig, FOREGROUND, escape, hex};
+use crate::data::Frame;
+use crate::util::ansi::{Span, spans, visible_width};
+
+/// advance width of the bundled font in em, 1233 of its 2048 units
+const ADVANCE: f64 = 1233.0 / 2048.0;
+
+/// size of a terminal cell and where the first one starts
+struct Grid {
+    font_size: f64,
+    cell_width: f64,
+    line_height: f64,
+    left: f64,
+    top: f64,
+}
+
+impl Grid {
+    fn new(config: &ExportConfig) -> Self {
+        let font_size = config.font_size() as f64;
+        let padding = config.padding() as f64;
+        let line_height = font_size * 1.25;
+        Grid {
+            font_size,
+            cell_width: font_size * ADVANCE,
+            line_height,
+            left: padding,
+            top: padding
+                + if config.chrome() {
+                    2.0 * line_height
+                } else {
+                    0.0
+                },
+        }
+    }
+
+    /// left edge of `column`
+    fn x(&self, column: usize) -> f64 {
+        self.left + column as f64 * self.cell_width
+    }
+
+    /// top edge of `row`
+    fn y(&self, row: usize) -> f64 {
+        self.top + row as f64 * self.line_height
+    }
+}
+
+/// Renders `frame` as an SVG document.
+///
+/// The bundled font is embedded so that text lines up with the cells whatever fonts the
+/// viewer has installed.
+///
+/// ```
+/// use symfetch::data::Frame;
+/// use symfetch::export::{svg::render, ExportConfig};
+///
+/// let frame = Frame { lines: vec!["\x1b[1;33mOS:\x1b[0m Linux <6.9>".to_string()], logo: None, image: None };
+/// let svg = render(&frame, &ExportConfig::default());
+/// assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+/// assert!(svg.contains(r##"<tspan x="16.0" fill="#cdcd00" font-weight="bold">OS:</tspan>"##));
+/// assert!(svg.contains("Linux &lt;6.9&gt;"));
+/// ```
+pub fn render(frame: &Frame, config: &ExportConfig) -> String {
+    let grid = Grid::new(config);
+    let columns = frame
+        .lines
+        .iter()
+        .map(|line| visible_width(line))
+        .max()
+        .unwrap_or(0);
+    let width = grid.x(columns) + grid.left;
+    let height = grid.y(frame.lines.len()) + config.padding() as f64;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.0}" height="{height:.0}" viewBox="0 0 {width:.1} {height:.1}">"#
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="100%" height="100%" fill="{}"/>"#,
+        hex(config.background())
+    );
+    if config.chrome() {
+        for (i, color) in CHROME_BUTTONS.iter().enumerate() {
+            let _ = writeln!(
+                svg,
+                r#"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}"/>"#,
+                grid.left + grid.line_height * (0.5 + 1.2 * i as f64),
+                config.padding() as f64 + grid.line_height * 0.5,
+                grid.line_height * 0.35,
+                hex(*color)
+            );
+        }
+    }
+    let _ = writeln!(
+        svg,
+        r#"<defs><style>@font-face{{font-family:"symfetch";src:url(data:font/ttf;base64,{})}}</style></defs>"#,
+        STANDARD.encode(BUNDLED_FONT)
+    );
+    let _ = writeln!(
+        svg,
+        r#"<g font-family="symfetch, 'DejaVu Sans Mono', monospace" font-size="{}" fill="{}" xml:space="preserve">"#,
+        grid.font_size,
+        hex(FOREGROUND)
+    );
+
+    for (row, line) in frame.lines.iter().enumerate() {
+        let top = grid.y(row);
+        let spans = spans(line);
+
+        // backgrounds go first so text is drawn on top of them
+        let mut column = 0;
+        for span in &spans {
+            let cells = span.text.chars().count();
+            if let Some(bg) = span.style.bg {
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{:.1}" y="{top:.1}" width="{:.1}" height="{:.1}" fill="{}"/>"#,
+                    grid.x(column),
+                    cells as f64 * grid.cell_width,
+                    grid.line_height,
+                    hex(bg)
+                );
+            }
+            column += cells;
+        }
+
+        let baseline = top + grid.font_size;
+        let _ = write!(svg, r#"<text y="{baseline:.1}">"#);
+        let mut column = 0;
+        for span in &spans {
+            if !span.text.trim().is_empty() {
+                let _ = write!(svg, "{}", tspan(span, grid.x(column)));
+            }
+            column += span.text.chars().count();
+        }
+        let _ = writeln!(svg, "</text>");
+    }
+    let _ = writeln!(svg, "</g>");
+
+    if let (Some(area), Some(image)) = (frame.logo, &frame.image)
+        && let Ok(uri) = super::data_uri(image)
+    {
+        let _ = writeln!(
+            svg,
+            r#"<image x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" preserveAspectRatio="xMidYMid meet" href="{uri}"/>"#,
+            grid.x(area.column),
+            grid.y(area.row),
+            area.width as f64 * grid.cell_width,
+            area.height as f64 * grid.line_height
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn tspan(span: &Span, x: f64) -> String {
+    let mut attributes = String::new();
+    if let Some(fg) = span.style.fg {
+        let _ = write!(attributes, r#" fill="{}""#, hex(fg));
+    }
+    if span.style.bold {
+        attributes.push_str(r#" font-weight="bold""#);
+    }
+    if span.style.italic {
+        attributes.push_str(r#" font-style="italic""#);
+    }
+    if span.style.underline {
+        attributes.push_str(r#" text-decoration="underline""#);
+    }
+    if span.style.dim {
+        attributes.push_str(r#" opacity="0.6""#);
+    }
+    format!(
+        r#"<tspan x="{x:.1}"{attributes}>{}</tspan>"#,
+        escape(&span.text)
+    )
+}
@@ -0,0 +1,466 @@
+//! arrangement of the logo and the info lines
+use std::iter;
+use std::path::PathBuf;
+
+use colored::{Color, Colorize};
+use serde_derive::Deserialize;
+
+use crate::util::ansi::{pad, visible_width};
+
+/// where the logo is placed relative to the info lines
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogoPosition {
+    #[default]
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// only print the info lines
+    None,
+}
+
+/// vertical alignment of the shorter of logo and info when placed side by side
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Align {
+    #[default]
+    Top,
+    Center,
+    Bottom,
+}
+
+/// store parsed information from the "\[layout\]" table
+///
+/// ```toml
+/// [layout]
+/// # Optional: logo = "left"
+/// # Optional: gap = 1
+/// # Optional: padding = 0
+/// # Optional: separator = "|"
+/// # Optional: align = "top"
+/// # Optional: columns = 1
+/// # Optional: fit = true
+/// # Optional: compact_logo = "~/.config/symfetch/ascii-small"
+/// # Optional: rule = "─"
+/// # Optional: title_color = "blue"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct LayoutConfig {
+    /// `left`, `right`, `top`, `bottom` or `none`
+    pub logo: Option<LogoPosition>,
+
+    /// spaces on each side of the separator, or blank lines when the logo is on top/bottom
+    pub gap: Option<usize>,
+
+    /// spaces in front of every line
+    pub padding: Option<usize>,
+
+    /// drawn between logo and info, an empty string leaves only the gap
+    pub separator: Option<String>,
+
+    /// `top`, `center` or `bottom`
+    pub align: Option<Align>,
+
+    /// split the info lines into this many columns (1 or 2) if the terminal is wide enough
+    pub columns: Option<usize>,
+
+    /// shrink the image, switch to `compact_logo`, move the logo on top and finally
+    /// truncate lines until the output fits into the terminal
+    pub fit: Option<bool>,
+
+    /// smaller ASCII art used when the logo doesn't fit next to the info lines
+    pub compact_logo: Option<PathBuf>,
+
+    /// character repeated for the rules below group titles, an empty string disables them
+    pub rule: Option<String>,
+
+    /// color of group titles, e.g. `blue` or `bright magenta`
+    pub title_color: Option<String>,
+
+    /// sections of the info column, modules that are in no group are listed after them
+    #[serde(default)]
+    pub group: Vec<GroupConfig>,
+}
+
+/// store parsed information from a "\[\[layout.group\]\]" entry
+///
+/// ```toml
+/// [[layout.group]]
+/// title = "Hardware"
+/// modules = ["host", "cpu", "gpu", "spacer", "memory", "swap", "rule", "storage"]
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct GroupConfig {
+    /// shown above the group, followed by a rule
+    pub title: Option<String>,
+
+    /// modules in the order they are shown, `spacer` adds a blank line and `rule` a rule
+    pub modules: Vec<String>,
+}
+
+impl LayoutConfig {
+    /// Returns the number of columns [`compose`] needs to print everything without wrapping.
+    ///
+    /// ```
+    /// use symfetch::layout::LayoutConfig;
+    ///
+    /// let info = vec!["OS: Linux".to_string()];
+    /// assert_eq!(LayoutConfig::default().required_width(20, &info), 32);
+    /// ```
+    pub fn required_width(&self, logo_width: usize, info: &[String]) -> usize {
+        let padding = self.padding.unwrap_or(0);
+        let info_width = info
+            .iter()
+            .map(|line| visible_width(line))
+            .max()
+            .unwrap_or(0);
+        match self.logo.unwrap_or_default() {
+            LogoPosition::Left | LogoPosition::Right => {
+                padding + logo_width + visible_width(&self.separator()) + info_width
+            }
+            LogoPosition::Top | LogoPosition::Bottom => padding + logo_width.max(info_width),
+            LogoPosition::None => padding + info_width,
+        }
+    }
+
+    /// Checks that `columns` is 1 or 2, that groups only list known modules and that the
+    /// title color exists.
+    ///
+    /// # Arguments
+    /// * `modules` - names of all modules, see [`MODULE_NAMES`](crate::system_info::MODULE_NAMES)
+    ///
+    /// ```
+    /// use symfetch::layout::{GroupConfig, LayoutConfig};
+    ///
+    /// let config = LayoutConfig {
+    ///     group: vec![GroupConfig { title: None, modules: vec!["cpu".to_string(), "ram".to_string()] }],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     config.validate(&["cpu", "memory"]).unwrap_err(),
+    ///     "[[layout.group]] unknown module `ram`, expected one of cpu, memory, spacer, rule"
+    /// );
+    ///
+    /// let config = LayoutConfig { columns: Some(3), ..Default::default() };
+    /// assert_eq!(config.validate(&[]).unwrap_err(), "[layout] columns: expected 1 or 2, got 3");
+    /// ```
+    pub fn validate(&self, modules: &[&str]) -> Result<(), String> {
+        if let Some(columns) = self.columns
+            && !matches!(columns, 1 | 2)
+        {
+            return Err(format!("[layout] columns: expected 1 or 2, got {columns}"));
+        }
+        if let Some(color) = &self.title_color {
+            color
+                .parse::<Color>()
+                .map_err(|_| format!("[layout] title_color: unknown color `{color}`"))?;
+        }
+        for module in self.group.iter().flat_map(|group| &group.modules) {
+            if !modules.contains(&module.as_str()) && !PSEUDO_MODULES.contains(&module.as_str()) {
+                return Err(format!(
+                    "[[layout.group]] unknown module `{module}`, expected one of {}, {}",
+                    modules.join(", "),
+                    PSEUDO_MODULES.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// the separator including the gap on both sides
+    fn separator(&self) -> String {
+        let gap = self.gap.unwrap_or(1);
+        match self.separator.as_deref().unwrap_or("|") {
+            "" => " ".repeat(gap),
+            separator => format!("{0}{separator}{0}", " ".repeat(gap)),
+        }
+    }
+}
+
+/// entries of a group that aren't modules
+const PSEUDO_MODULES: &[&str] = &["spacer", "rule"];
+
+/// Sorts info lines into the groups of `config`, adding titles, rules and spacers.
+///
+/// # Arguments
+/// * `lines` - info lines tagged with the module that produced them
+/// * `config` - the `[layout]` table containing the groups
+///
+/// ```
+/// use symfetch::layout::{group_lines, GroupConfig, LayoutConfig};
+///
+/// colored::control::set_override(false);
+/// let lines = vec![
+///     ("os", "OS: Linux".to_string()),
+///     ("cpu", "CPU: Ryzen 7 7840U".to_string()),
+///     ("memory", "Memory: 4GB / 16GB".to_string()),
+///     ("shell", "Shell: fish".to_string()),
+/// ];
+/// let config = LayoutConfig {
+///     rule: Some("-".to_string()),
+///     group: vec![GroupConfig {
+///         title: Some("Hardware".to_string()),
+///         modules: vec!["cpu".to_string(), "spacer".to_string(), "memory".to_string()],
+///     }],
+///     ..Default::default()
+/// };
+/// assert_eq!(
+///     group_lines(&lines, &config),
+///     vec![
+///         "Hardware",
+///         "------------------",
+///         "CPU: Ryzen 7 7840U",
+///         "",
+///         "Memory: 4GB / 16GB",
+///         "",
+///         "OS: Linux",
+///         "Shell: fish",
+///     ]
+/// );
+/// ```
+pub fn group_lines(lines: &[(&str, String)], config: &LayoutConfig) -> Vec<String> {
+    let rule = config.rule.as_deref().unwrap_or("─");
+    let title_color = config
+        .title_color
+        .as_deref()
+        .and_then(|color| color.parse::<Color>().ok())
+        .unwrap_or(Color::Blue);
+
+    let mut out: Vec<String> = Vec::new();
+    for group in &config.group {
+        // `None` stands for a rule, which can only be sized once the group is complete
+        let body: Vec<Option<String>> = group
+            .modules
+            .iter()
+            .flat_map(|module| match module.as_str() {
+                "spacer" => vec![Some(String::new())],
+                "rule" => vec![None],
+                module => lines
+                    .iter()
+                    .filter(|(name, _)| *name == module)
+                    .map(|(_, line)| Some(line.clone()))
+                    .collect(),
+            })
+            .collect();
+        if !body.iter().flatten().any(|line| !line.is_empty()) {
+            continue;
+        }
+
+        let width = body
+            .iter()
+            .flatten()
+            .map(|line| visible_width(line))
+            .chain(group.title.iter().map(|title| visible_width(title)))
+            .max()
+            .unwrap_or(0);
+        let rule_line = rule.repeat(width).dimmed().to_string();
+
+        if !out.is_empty() {
+            out.push(String::new());
+        }
+        if let Some(title) = &group.title {
+            out.push(title.bold().color(title_color).to_string());
+            if !rule.is_empty() {
+                out.push(rule_line.clone());
+            }
+        }
+        out.extend(
+            body.into_iter()
+                .map(|line| line.unwrap_or_else(|| rule_line.clone())),
+        );
+    }
+
+    let grouped: Vec<&str> = config
+        .group
+        .iter()
+        .flat_map(|group| group.modules.iter().map(String::as_str))
+        .collect();
+    let rest: Vec<String> = lines
+        .iter()
+        .filter(|(name, _)| !grouped.contains(name))
+        .map(|(_, line)| line.clone())
+        .collect();
+    if !rest.is_empty() {
+        if !out.is_empty() {
+            out.push(String::new());
+        }
+        out.extend(rest);
+    }
+    out
+}
+
+/// Arranges the logo and info lines according to `config`.
+///
+/// Also returns the column and row of the top left corner of the logo, exporters use it to
+/// draw the original image in place of its ANSI rendering.
+///
+/// # Arguments
+/// * `logo` - lines of the logo, may contain escape sequences
+/// * `logo_width` - visible width of the logo
+/// * `info` - info lines, the first two (user@host and a blank line) are never split into columns
+/// * `config` - the `[layout]` table
+/// * `term_width` - width of the terminal, `None` if unknown
+///
+/// ```
+/// use symfetch::layout::{compose, Align, LayoutConfig, LogoPosition};
+///
+/// let logo = vec!["/\\".to_string(), "\\/".to_string()];
+/// let info = vec!["user@host".to_string(), String::new(), "OS: Linux".to_string()];
+///
+/// let (lines, origin) = compose(&logo, 2, &info, &LayoutConfig::default(), None);
+/// assert_eq!(lines, vec!["/\\ | user@host", "\\/ | ", "   | OS: Linux"]);
+/// assert_eq!(origin, Some((0, 0)));
+///
+/// let config = LayoutConfig {
+///     logo: Some(LogoPosition::Right),
+///     separator: Some(String::new()),
+///     gap: Some(2),
+///     align: Some(Align::Bottom),
+///     ..Default::default()
+/// };
+/// let (lines, origin) = compose(&logo, 2, &info, &config, None);
+/// assert_eq!(lines, vec!["user@host", "           /\\", "OS: Linux  \\/"]);
+/// assert_eq!(origin, Some((11, 1)));
+/// ```
+pub fn compose(
+    logo: &[String],
+    logo_width: usize,
+    info: &[String],
+    config: &LayoutConfig,
+    term_width: Option<usize>,
+) -> (Vec<String>, Option<(usize, usize)>) {
+    let gap = config.gap.unwrap_or(1);
+    let padding = " ".repeat(config.padding.unwrap_or(0));
+    let separator = config.separator();
+    let position = if logo.is_empty() {
+        LogoPosition::None
+    } else {
+        config.logo.unwrap_or_default()
+    };
+
+    // space left for the info lines next to the logo
+    let available = term_width.map(|width| match position {
+        LogoPosition::Left | LogoPosition::Right => {
+            width.saturating_sub(padding.len() + logo_width + visible_width(&separator))
+        }
+        _ => width.saturating_sub(padding.len()),
+    });
+    let info = match config.columns.unwrap_or(1) {
+        2 => two_columns(info, gap.max(1) * 2, available),
+        _ => info.to_vec(),
+    };
+
+    let (lines, origin) = match position {
+        LogoPosition::None => (info, None),
+        LogoPosition::Top | LogoPosition::Bottom => {
+            let blank = iter::repeat_n(String::new(), gap);
+            if position == LogoPosition::Top {
+                let lines = logo.iter().cloned().chain(blank).chain(info).collect();
+                (lines, Some((0, 0)))
+            } else {
+                let row = info.len() + gap;
+                let lines = info
+                    .into_iter()
+                    .chain(blank)
+                    .chain(logo.iter().cloned())
+                    .collect();
+                (lines, Some((0, row)))
+            }
+        }
+        LogoPosition::Left | LogoPosition::Right => {
+            let height = logo.len().max(info.len());
+            let align = config.align.unwrap_or_default();
+            let row = offset(logo.len(), height, align);
+            let logo = aligned(logo, height, align);
+            let info = aligned(&info, height, align);
+            let info_width = info
+                .iter()
+                .flatten()
+                .map(|line| visible_width(line))
+                .max()
+                .unwrap_or(0);
+
+            let column = match position {
+                LogoPosition::Left => 0,
+                _ => info_width + visible_width(&separator),
+            };
+
+            let lines = logo
+                .iter()
+                .zip(&info)
+                .map(|(logo_line, info_line)| {
+                    let logo_line = match logo_line {
+                        Some(line) => pad(line, logo_width),
+                        None => " ".repeat(logo_width),
+                    };
+                    let info_line = info_line.clone().unwrap_or_default();
+                    if position == LogoPosition::Left {
+                        format!("{logo_line}{separator}{info_line}")
+                    } else {
+                        format!("{}{separator}{logo_line}", pad(&info_line, info_width))
+                            .trim_end()
+                            .to_string()
+                    }
+                })
+                .collect();
+            (lines, Some((column, row)))
+        }
+    };
+
+    let lines = lines
+        .into_iter()
+        .map(|line| format!("{padding}{line}"))
+        .collect();
+    let origin = origin.map(|(column, row)| (column + padding.len(), row));
+    (lines, origin)
+}
+
+/// places `lines` within `height` rows, `None` marks the rows left empty
+fn aligned<T: Clone>(lines: &[T], height: usize, align: Align) -> Vec<Option<T>> {
+    let free = height.saturating_sub(lines.len());
+    let before = offset(lines.len(), height, align);
+    iter::repeat_n(None, before)
+        .chain(lines.iter().cloned().map(Some))
+        .chain(iter::repeat_n(None, free - before))
+        .collect()
+}
+
+/// number of empty rows above `len` lines aligned within `height` rows
+fn offset(len: usize, height: usize, align: Align) -> usize {
+    let free = height.saturating_sub(len);
+    match align {
+        Align::Top => 0,
+        Align::Center => free / 2,
+        Align::Bottom => free,
+    }
+}
+
+/// splits the info lines below the header into two columns if they fit into `available`
+fn two_columns(info: &[String], gap: usize, available: Option<usize>) -> Vec<String> {
+    let Some(available) = available else {
+        return info.to_vec();
+    };
+    let header = info.len().min(2);
+    let body = &info[header..];
+    let (left, right) = body.split_at(body.len().div_ceil(2));
+    let left_width = left
+        .iter()
+        .map(|line| visible_width(line))
+        .max()
+        .unwrap_or(0);
+    let right_width = right
+        .iter()
+        .map(|line| visible_width(line))
+        .max()
+        .unwrap_or(0);
+    if left_width + gap + right_width > available {
+        return info.to_vec();
+    }
+
+    let rows = left.iter().enumerate().map(|(i, line)| match right.get(i) {
+        Some(right) => format!("{}{}{right}", pad(line, left_width), " ".repeat(gap)),
+        None => line.clone(),
+    });
+    info[..header].iter().cloned().chain(rows).collect()
+}
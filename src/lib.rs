@@ -54,10 +54,169 @@
 //! # Optional: colored = true
 //! ```
 //!
+//! ### Modules
+//!
+//! Individual info modules are configured through sub-tables of `[module]`.
+//!
+//! ```toml
+//! [module.battery]
+//! health = false
+//!
+//! [module.colors]
+//! enabled = true
+//! symbol = "●"
+//! gradient = "256"
+//!
+//! [module.cpu]
+//! temperature = false
+//!
+//! [module.date]
+//! format = "%a %d %b %H:%M"
+//!
+//! [[module.custom]]
+//! label = "Cluster"
+//! command = "kubectl config current-context"
+//! timeout = 2
+//! cache_ttl = 60
+//!
+//! [module.disk]
+//! show_mounts = true
+//! exclude = ["/boot*"]
+//! exclude_fs = ["vfat"]
+//!
+//! [module.init]
+//! failed_units = false
+//!
+//! [module.load]
+//! normalize = false
+//!
+//! [module.network]
+//! hide_addresses = true
+//!
+//! [module.packages]
+//! exclude = ["cargo"]
+//!
+//! [module.shell]
+//! version = false
+//!
+//! [module.terminal]
+//! version = false
+//! ```
+//!
+//! ### Usage bars
+//!
+//! Resource lines (memory, storage, CPU usage, ...) can be decorated with a bar graph
+//! and colored green/yellow/red depending on how much of the resource is in use.
+//!
+//! ```toml
+//! [usage]
+//! bar = true
+//! bar_width = 10
+//! colored = true
+//! warning = 60
+//! critical = 85
+//! ```
+//!
+//! ### Line formats
+//!
+//! The value of every module line except `virtualization`, `displays`, `font`, `cpu_usage`,
+//! `gpu`, `custom` and `colors` can be replaced by a `format` string in the table of its
+//! module. `{name}` inserts a placeholder, `{?name:text}` renders `text` only if `name`
+//! has a value and `{{`/`}}` are literal braces. Unknown placeholders are reported when
+//! the config is loaded.
+//!
+//! ```toml
+//! [module.memory]
+//! format = "{used} / {total} ({percent}%)"
+//!
+//! [module.shell]
+//! format = "{name}{?version: v{version}}"
+//! ```
+//!
+//! The `host` and `theme` modules print up to three lines by default, a `format` merges
+//! them into one.
+//!
+//! ```toml
+//! [module.theme]
+//! format = "{theme}{?icons:, {icons} icons}"
+//! ```
+//!
+//! ### Layout
+//!
+//! The `[layout]` table controls where the logo goes and how it is separated from the info lines.
+//!
+//! ```toml
+//! [layout]
+//! logo = "right"      # left, right, top, bottom or none
+//! gap = 2
+//! padding = 1
+//! separator = ""      # no separator, just the gap
+//! align = "center"    # top, center or bottom
+//! columns = 2         # only used if the terminal is wide enough
+//! ```
+//!
+//! When the output is wider than the terminal, symfetch shrinks the image, switches to
+//! `compact_logo`, moves the logo above the info lines and finally cuts off long lines
+//! with `…`. Set `fit = false` to print everything as is.
+//!
+//! ```toml
+//! [layout]
+//! compact_logo = "~/.config/symfetch/ascii-small"
+//! ```
+//!
+//! Longer outputs can be split into titled groups. Modules that are in no group
+//! are listed after the last one.
+//!
+//! ```toml
+//! [layout]
+//! title_color = "magenta"
+//! rule = "─"
+//!
+//! [[layout.group]]
+//! title = "Hardware"
+//! modules = ["host", "cpu", "gpu", "spacer", "memory", "swap", "rule", "storage"]
+//!
+//! [[layout.group]]
+//! title = "Session"
+//! modules = ["shell", "terminal", "wm"]
+//! ```
+//!
+//! ### Export
+//!
+//! `--export out.svg` writes the output to an SVG file instead of the terminal, keeping
+//! colors and layout. Image logos are embedded as they are rather than as ANSI blocks, and
+//! so is the bundled font. Exports are never fitted to the width of the terminal.
+//! `--export out.png` renders a screenshot instead, using a bundled DejaVu Sans Mono
+//! unless another font is configured. `--export out.html` produces a page with inline
+//! styles only, ready to be embedded elsewhere.
+//!
+//! ```sh
+//! symfetch --export out.svg
+//! ```
+//!
+//! ```toml
+//! [export]
+//! font_size = 16
+//! background = "#282a36"
+//! padding = 24
+//! chrome = true
+//! ```
+//!
+//! ### Watch
+//!
+//! `--watch` keeps the output on screen and refreshes it every 2 seconds, or as often as
+//! given, e.g. `--watch 0.5`. Memory, swap, load, processes, battery, network throughput,
+//! storage and uptime are updated, everything else is gathered once. Quit with `q`,
+//! `Esc` or `Ctrl-C`.
+//!
 //! ## Brought to you by
 //!
 //! ![](https://github.com/SymmetrySyndicate/.github/blob/main/assets/banner/twitter_banner.png?raw=true)
 pub mod config_handler;
 pub mod data;
+pub mod export;
+pub mod layout;
+pub mod modules;
 pub mod system_info;
 pub mod util;
+pub mod watch;
@@ -1,12 +1,16 @@
 use clap::{arg, command, value_parser};
 use config_handler::Config;
 use data::Data;
-use std::{env, path::PathBuf};
+use std::{env, path::PathBuf, process::exit, time::Duration};
 
 mod config_handler;
 mod data;
+mod export;
+mod layout;
+mod modules;
 mod system_info;
 mod util;
+mod watch;
 
 #[allow(unused_variables)]
 fn main() {
@@ -18,6 +22,22 @@ fn main() {
             .required(false)
             .value_parser(value_parser!(PathBuf)),
         )
+        .arg(
+            arg!(
+                --export <FILE> "Writes the output to an SVG, PNG or HTML file instead of printing it"
+            )
+            .required(false)
+            .value_parser(value_parser!(PathBuf)),
+        )
+        .arg(
+            arg!(
+                --watch [SECONDS] "Keeps refreshing the output every few seconds, quit with q"
+            )
+            .required(false)
+            .num_args(0..=1)
+            .default_missing_value(watch::DEFAULT_INTERVAL)
+            .value_parser(value_parser!(f64)),
+        )
         .get_matches();
 
     let config_path = matches
@@ -31,6 +51,29 @@ fn main() {
     let config = Config::new(&config_path).unwrap();
     let data = Data::new(config);
 
+    if let Some(path) = matches.get_one::<PathBuf>("export") {
+        if let Err(err) = data.export(path) {
+            eprintln!("Failed to export to {}: {err}", path.display());
+            exit(1);
+        }
+        return;
+    }
+
+    if let Some(&seconds) = matches.get_one::<f64>("watch") {
+        let interval = match Duration::try_from_secs_f64(seconds) {
+            Ok(interval) if !interval.is_zero() => interval,
+            _ => {
+                eprintln!("--watch needs a positive number of seconds, got {seconds}");
+                exit(1);
+            }
+        };
+        if let Err(err) = watch::run(&data, interval) {
+            eprintln!("Failed to watch: {err}");
+            exit(1);
+        }
+        return;
+    }
+
     // Single call to render everything with system info on the right side
     data.render();
 }
@@ -0,0 +1,159 @@
+//! battery and power supply state
+use std::fs;
+use std::path::Path;
+
+use serde_derive::Deserialize;
+
+use crate::util::fs_utils::read_trimmed;
+
+/// store parsed information from the "\[module.battery\]" table
+///
+/// ```toml
+/// [module.battery]
+/// # Optional: time = true
+/// # Optional: health = true
+/// # Optional: format = "{capacity}% {status}{?time: ({time} left)}"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct BatteryConfig {
+    /// show the estimated time until empty or full
+    pub time: Option<bool>,
+
+    /// show the remaining capacity compared to the design capacity
+    pub health: Option<bool>,
+
+    /// template for the value of the line, see [`Template`](crate::util::template::Template)
+    pub format: Option<String>,
+}
+
+/// a single battery from `/sys/class/power_supply`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Battery {
+    /// name of the supply, e.g. `BAT0`
+    pub name: String,
+    /// charge in percent
+    pub capacity: u64,
+    /// `Charging`, `Discharging`, `Full`, ...
+    pub status: String,
+    /// minutes until empty while discharging or until full while charging
+    pub minutes_left: Option<u64>,
+    /// full capacity compared to the design capacity in percent
+    pub health: Option<u64>,
+}
+
+impl Battery {
+    /// Formats the battery line according to `config`.
+    ///
+    /// ```
+    /// use symfetch::modules::battery::{Battery, BatteryConfig};
+    ///
+    /// let battery = Battery {
+    ///     name: "BAT0".to_string(),
+    ///     capacity: 80,
+    ///     status: "Discharging".to_string(),
+    ///     minutes_left: Some(240),
+    ///     health: Some(91),
+    /// };
+    /// assert_eq!(
+    ///     battery.format(&BatteryConfig::default()),
+    ///     "80% [Discharging, 4h 0m left] (health 91%)"
+    /// );
+    /// ```
+    pub fn format(&self, config: &BatteryConfig) -> String {
+        let mut line = format!("{}% [{}", self.capacity, self.status);
+        if config.time.unwrap_or(true)
+            && let Some(minutes) = self.minutes_left
+        {
+            line.push_str(&format!(", {}h {}m left", minutes / 60, minutes % 60));
+        }
+        line.push(']');
+        if config.health.unwrap_or(true)
+            && let Some(health) = self.health
+        {
+            line.push_str(&format!(" (health {health}%)"));
+        }
+        line
+    }
+}
+
+/// batteries and AC adapters present on the system
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PowerSupply {
+    pub batteries: Vec<Battery>,
+    /// whether an AC adapter is plugged in, `None` if there is no adapter
+    pub ac_online: Option<bool>,
+}
+
+/// Reads every supply below `sys/class/power_supply`.
+///
+/// Desktops have no batteries, in which case [`PowerSupply::batteries`] is empty.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `sys/`, `/` on a live system
+pub fn probe(root: &Path) -> PowerSupply {
+    let mut supplies: Vec<_> = fs::read_dir(root.join("sys/class/power_supply"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    supplies.sort();
+
+    let mut power = PowerSupply::default();
+    for dir in supplies {
+        let read = |file: &str| read_trimmed(&dir.join(file));
+        let read_u64 = |file: &str| read(file).and_then(|value| value.parse::<u64>().ok());
+
+        match read("type").as_deref() {
+            Some("Battery") => {
+                // some batteries report energy (µWh), others charge (µAh)
+                let (now, full, design, rate) = match read_u64("energy_now") {
+                    Some(now) => (
+                        Some(now),
+                        read_u64("energy_full"),
+                        read_u64("energy_full_design"),
+                        read_u64("power_now"),
+                    ),
+                    None => (
+                        read_u64("charge_now"),
+                        read_u64("charge_full"),
+                        read_u64("charge_full_design"),
+                        read_u64("current_now"),
+                    ),
+                };
+                let status = read("status").unwrap_or_else(|| "Unknown".to_string());
+                let minutes_left = match (status.as_str(), now, full, rate) {
+                    (_, _, _, None | Some(0)) => None,
+                    ("Discharging", Some(now), _, Some(rate)) => Some(now * 60 / rate),
+                    ("Charging", Some(now), Some(full), Some(rate)) => {
+                        Some(full.saturating_sub(now) * 60 / rate)
+                    }
+                    _ => None,
+                };
+                let capacity = read_u64("capacity").or_else(|| {
+                    now.zip(full)
+                        .and_then(|(now, full)| (now * 100).checked_div(full))
+                });
+
+                power.batteries.push(Battery {
+                    name: dir
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    capacity: capacity.unwrap_or(0).min(100),
+                    status,
+                    minutes_left,
+                    health: full
+                        .zip(design)
+                        .and_then(|(full, design)| (full * 100).checked_div(design)),
+                });
+            }
+            Some("Mains") => {
+                let online = read("online").as_deref() == Some("1");
+                power.ac_online = Some(power.ac_online.unwrap_or(false) || online);
+            }
+            _ => {}
+        }
+    }
+    power
+}
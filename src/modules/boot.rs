@@ -0,0 +1,107 @@
+//! boot mode, Secure Boot state, bootloader and boot time
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Local};
+
+/// vendor GUID of the EFI global variables such as `SecureBoot`
+const EFI_GLOBAL: &str = "8be4df61-93ca-11d2-aa0d-e98032b8c39c";
+
+/// vendor GUID of the variables set by systemd-boot and other Boot Loader Interface loaders
+const LOADER_INTERFACE: &str = "4a67b082-0a4c-41cf-b6c7-440b29bb8c4f";
+
+/// how the system was booted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Boot {
+    /// booted through UEFI rather than legacy BIOS
+    pub uefi: bool,
+    /// `None` when booted through BIOS or the variable can't be read
+    pub secure_boot: Option<bool>,
+    pub bootloader: Option<String>,
+    pub boot_time: Option<DateTime<Local>>,
+}
+
+impl Boot {
+    /// Formats as `UEFI (Secure Boot on), systemd-boot 255.4`.
+    pub fn format(&self) -> String {
+        let mut line = if self.uefi { "UEFI" } else { "BIOS" }.to_string();
+        match self.secure_boot {
+            Some(true) => line.push_str(" (Secure Boot on)"),
+            Some(false) => line.push_str(" (Secure Boot off)"),
+            None => {}
+        }
+        if let Some(bootloader) = &self.bootloader {
+            line.push_str(&format!(", {bootloader}"));
+        }
+        line
+    }
+}
+
+/// Detects the boot mode and bootloader.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `sys/`, `proc/` and `boot/`, `/` on a live system
+/// * `boot_time` - seconds since the epoch at which the system booted, `0` if unknown
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::modules::boot::probe;
+///
+/// let boot = probe(Path::new("tests/fixtures/root"), 0);
+/// assert_eq!(boot.format(), "UEFI (Secure Boot on), systemd-boot 255.4");
+/// ```
+pub fn probe(root: &Path, boot_time: u64) -> Boot {
+    let efivars = root.join("sys/firmware/efi/efivars");
+    let uefi = root.join("sys/firmware/efi").exists();
+
+    // efivars start with 4 bytes of attributes followed by the value
+    let secure_boot = fs::read(efivars.join(format!("SecureBoot-{EFI_GLOBAL}")))
+        .ok()
+        .and_then(|var| var.get(4).map(|enabled| *enabled == 1));
+
+    let bootloader = fs::read(efivars.join(format!("LoaderInfo-{LOADER_INTERFACE}")))
+        .ok()
+        .and_then(|var| utf16_value(&var))
+        .or_else(|| {
+            let cmdline = fs::read_to_string(root.join("proc/cmdline")).unwrap_or_default();
+            let grub = cmdline.contains("BOOT_IMAGE=")
+                || root.join("boot/grub/grub.cfg").exists()
+                || root.join("boot/grub2/grub.cfg").exists();
+            if grub {
+                Some("GRUB".to_string())
+            } else if root.join("boot/efi/EFI/refind").exists() {
+                Some("rEFInd".to_string())
+            } else if root.join("boot/limine.conf").exists()
+                || root.join("boot/limine/limine.conf").exists()
+            {
+                Some("Limine".to_string())
+            } else if root.join("boot/syslinux").exists() {
+                Some("Syslinux".to_string())
+            } else {
+                None
+            }
+        });
+
+    Boot {
+        uefi,
+        secure_boot: if uefi { secure_boot } else { None },
+        bootloader,
+        boot_time: (boot_time > 0)
+            .then(|| DateTime::from_timestamp(boot_time as i64, 0))
+            .flatten()
+            .map(|time| time.with_timezone(&Local)),
+    }
+}
+
+/// decodes a NUL terminated UTF-16LE efivar value
+fn utf16_value(var: &[u8]) -> Option<String> {
+    let units: Vec<u16> = var
+        .get(4..)?
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+    String::from_utf16(&units)
+        .ok()
+        .filter(|value| !value.is_empty())
+}
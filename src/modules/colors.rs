@@ -0,0 +1,158 @@
+//! color blocks showing the palette of the terminal
+use serde_derive::Deserialize;
+
+/// store parsed information from the "\[module.colors\]" table
+///
+/// ```toml
+/// [module.colors]
+/// # Optional: enabled = true
+/// # Optional: block_width = 3
+/// # Optional: symbol = "●"
+/// # Optional: rows = 2
+/// # Optional: gradient = "truecolor"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct ColorsConfig {
+    /// print the blocks below the info lines, off by default
+    pub enabled: Option<bool>,
+
+    /// how many times `symbol` is repeated per color
+    pub block_width: Option<usize>,
+
+    /// drawn in the foreground color, the blocks are filled with the background color if unset
+    pub symbol: Option<String>,
+
+    /// split the 16 colors over 1 or 2 rows
+    pub rows: Option<usize>,
+
+    /// add a `256` color or `truecolor` strip below the blocks
+    pub gradient: Option<String>,
+}
+
+impl ColorsConfig {
+    /// Checks that `rows` is 1 or 2 and `gradient` is `256` or `truecolor`.
+    ///
+    /// ```
+    /// use symfetch::modules::colors::ColorsConfig;
+    ///
+    /// let config = ColorsConfig { rows: Some(4), ..Default::default() };
+    /// assert_eq!(config.validate().unwrap_err(), "[module.colors] rows: expected 1 or 2, got 4");
+    /// ```
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(rows) = self.rows
+            && !matches!(rows, 1 | 2)
+        {
+            return Err(format!("[module.colors] rows: expected 1 or 2, got {rows}"));
+        }
+        if let Some(gradient) = &self.gradient
+            && !matches!(gradient.as_str(), "256" | "truecolor")
+        {
+            return Err(format!(
+                "[module.colors] gradient: expected `256` or `truecolor`, got `{gradient}`"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the visible width of the widest line printed by [`lines`].
+    ///
+    /// ```
+    /// use symfetch::modules::colors::ColorsConfig;
+    ///
+    /// assert_eq!(ColorsConfig::default().width(), 24);
+    /// ```
+    pub fn width(&self) -> usize {
+        let symbol_width = self
+            .symbol
+            .as_deref()
+            .map_or(1, |symbol| symbol.chars().count());
+        16 / self.rows() * self.block_width.unwrap_or(3) * symbol_width
+    }
+
+    /// other values are rejected by [`Self::validate`] when the config is loaded
+    fn rows(&self) -> usize {
+        match self.rows {
+            Some(1) => 1,
+            _ => 2,
+        }
+    }
+}
+
+/// Renders the 16 ANSI colors and the optional gradient strip.
+///
+/// ```
+/// use symfetch::modules::colors::{lines, ColorsConfig};
+///
+/// let config = ColorsConfig { block_width: Some(1), symbol: Some("#".to_string()), ..Default::default() };
+/// let lines = lines(&config);
+/// assert_eq!(lines.len(), 2);
+/// assert!(lines[0].starts_with("\x1b[30m#\x1b[0m\x1b[31m#"));
+/// assert!(lines[1].starts_with("\x1b[90m#"));
+/// ```
+pub fn lines(config: &ColorsConfig) -> Vec<String> {
+    let block_width = config.block_width.unwrap_or(3);
+    let block = |color: u8| {
+        // 0-7 are the normal colors, 8-15 their bright variants
+        let (normal, code) = if color < 8 {
+            (30, color)
+        } else {
+            (90, color - 8)
+        };
+        match &config.symbol {
+            Some(symbol) => format!(
+                "\x1b[{}m{}\x1b[0m",
+                normal + code,
+                symbol.repeat(block_width)
+            ),
+            None => format!(
+                "\x1b[{}m{}\x1b[0m",
+                normal + 10 + code,
+                " ".repeat(block_width)
+            ),
+        }
+    };
+
+    let per_row = 16 / config.rows();
+    let mut lines: Vec<String> = (0..16u8)
+        .collect::<Vec<_>>()
+        .chunks(per_row)
+        .map(|row| row.iter().map(|color| block(*color)).collect())
+        .collect();
+
+    let cells = config.width();
+    match config.gradient.as_deref() {
+        Some("truecolor") => lines.push(gradient(cells, |(r, g, b)| {
+            format!("\x1b[48;2;{r};{g};{b}m \x1b[0m")
+        })),
+        Some("256") => lines.push(gradient(cells, |(r, g, b)| {
+            // nearest color of the 6x6x6 cube starting at 16
+            let level = |c: u8| (c as u16 * 5 / 255) as u8;
+            let index = 16 + 36 * level(r) + 6 * level(g) + level(b);
+            format!("\x1b[48;5;{index}m \x1b[0m")
+        })),
+        _ => {}
+    }
+    lines
+}
+
+/// a hue sweep over `cells` columns
+fn gradient(cells: usize, cell: impl Fn((u8, u8, u8)) -> String) -> String {
+    (0..cells)
+        .map(|i| cell(hue(i as f64 / cells.max(1) as f64 * 360.0)))
+        .collect()
+}
+
+/// fully saturated color of the given hue in degrees
+fn hue(degrees: f64) -> (u8, u8, u8) {
+    let x = 1.0 - ((degrees / 60.0) % 2.0 - 1.0).abs();
+    let (r, g, b) = match degrees as u32 / 60 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    let scale = |c: f64| (c * 255.0).round() as u8;
+    (scale(r), scale(g), scale(b))
+}
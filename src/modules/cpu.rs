@@ -0,0 +1,264 @@
+//! CPU model, topology, frequency and temperature
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use serde_derive::Deserialize;
+use sysinfo::System;
+
+use crate::util::fs_utils::read_trimmed;
+
+/// hwmon drivers that report the CPU package temperature
+const HWMON_SENSORS: &[&str] = &["coretemp", "k10temp", "zenpower", "cpu_thermal"];
+
+/// thermal zones that report the CPU package temperature
+const THERMAL_ZONES: &[&str] = &["x86_pkg_temp", "cpu-thermal", "cpu_thermal", "soc_thermal"];
+
+/// store parsed information from the "\[module.cpu\]" table
+///
+/// ```toml
+/// [module.cpu]
+/// # Optional: topology = true
+/// # Optional: frequency = true
+/// # Optional: temperature = true
+/// # Optional: format = "{brand} ({cores}C/{threads}T)"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct CpuConfig {
+    /// show physical cores, threads and the hybrid P-core/E-core split
+    pub topology: Option<bool>,
+
+    /// show current and maximum frequency
+    pub frequency: Option<bool>,
+
+    /// show the package temperature
+    pub temperature: Option<bool>,
+
+    /// template for the value of the line, see [`Template`](crate::util::template::Template)
+    pub format: Option<String>,
+}
+
+/// everything we know about the CPU
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Cpu {
+    pub brand: String,
+    /// number of physical cores
+    pub cores: usize,
+    /// number of logical CPUs
+    pub threads: usize,
+    /// number of performance cores on hybrid CPUs
+    pub performance_cores: Option<usize>,
+    /// number of efficiency cores on hybrid CPUs
+    pub efficiency_cores: Option<usize>,
+    /// highest current frequency across all cores in MHz
+    pub current_mhz: Option<u64>,
+    /// maximum frequency in MHz
+    pub max_mhz: Option<u64>,
+    /// package temperature in degrees Celsius
+    pub temperature: Option<f32>,
+}
+
+impl Cpu {
+    /// Formats the CPU line according to `config`.
+    ///
+    /// ```
+    /// use symfetch::modules::cpu::{Cpu, CpuConfig};
+    ///
+    /// let cpu = Cpu {
+    ///     brand: "Intel(R) Core(TM) i7-1260P".to_string(),
+    ///     cores: 12,
+    ///     threads: 16,
+    ///     performance_cores: Some(4),
+    ///     efficiency_cores: Some(8),
+    ///     current_mhz: Some(2100),
+    ///     max_mhz: Some(4700),
+    ///     temperature: Some(45.0),
+    /// };
+    /// assert_eq!(
+    ///     cpu.format(&CpuConfig::default()),
+    ///     "Intel(R) Core(TM) i7-1260P (12C/16T, 4P + 8E) @ 2.10/4.70 GHz 45.0°C"
+    /// );
+    /// ```
+    pub fn format(&self, config: &CpuConfig) -> String {
+        let mut line = self.brand.clone();
+
+        if config.topology.unwrap_or(true) {
+            line.push_str(&format!(" ({}C/{}T", self.cores, self.threads));
+            if let (Some(p), Some(e)) = (self.performance_cores, self.efficiency_cores) {
+                line.push_str(&format!(", {p}P + {e}E"));
+            }
+            line.push(')');
+        } else {
+            line.push_str(&format!(" ({} cores)", self.threads));
+        }
+
+        if config.frequency.unwrap_or(true) {
+            match (self.current_mhz, self.max_mhz) {
+                (Some(current), Some(max)) if current != max => line.push_str(&format!(
+                    " @ {:.2}/{:.2} GHz",
+                    current as f64 / 1000.0,
+                    max as f64 / 1000.0
+                )),
+                (Some(mhz), _) | (None, Some(mhz)) => {
+                    line.push_str(&format!(" @ {:.2} GHz", mhz as f64 / 1000.0))
+                }
+                (None, None) => {}
+            }
+        }
+
+        if config.temperature.unwrap_or(true)
+            && let Some(temperature) = self.temperature
+        {
+            line.push_str(&format!(" {temperature:.1}°C"));
+        }
+
+        line
+    }
+}
+
+/// Probes the CPU, preferring sysfs under `root` and falling back to `sys`.
+///
+/// # Arguments
+/// * `sys` - an already refreshed [`System`]
+/// * `root` - filesystem root containing `sys/`, `/` on a live system
+pub fn probe(sys: &System, root: &Path) -> Cpu {
+    let cpu_dir = root.join("sys/devices/system/cpu");
+    let cpus = logical_cpus(&cpu_dir);
+
+    let brand = sys
+        .cpus()
+        .first()
+        .map(|cpu| cpu.brand().trim().to_string())
+        .filter(|brand| !brand.is_empty())
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let threads = if cpus.is_empty() {
+        sys.cpus().len()
+    } else {
+        cpus.len()
+    };
+    let cores = match physical_cores(&cpu_dir, &cpus) {
+        0 => System::physical_core_count().unwrap_or(threads),
+        cores => cores,
+    };
+
+    let hybrid = |kind: &str| {
+        read_trimmed(&root.join("sys/devices").join(kind).join("cpus"))
+            .map(|list| physical_cores(&cpu_dir, &parse_cpu_list(&list)))
+    };
+    let (performance_cores, efficiency_cores) = match (hybrid("cpu_core"), hybrid("cpu_atom")) {
+        (Some(p), Some(e)) => (Some(p), Some(e)),
+        _ => (None, None),
+    };
+
+    let freq = |file: &str| {
+        cpus.iter()
+            .filter_map(|cpu| read_trimmed(&cpu_dir.join(cpu).join("cpufreq").join(file)))
+            .filter_map(|khz| khz.parse::<u64>().ok())
+            .max()
+            .map(|khz| khz / 1000)
+    };
+    let current_mhz = freq("scaling_cur_freq").or_else(|| {
+        sys.cpus()
+            .iter()
+            .map(|cpu| cpu.frequency())
+            .max()
+            .filter(|mhz| *mhz > 0)
+    });
+    let max_mhz = freq("cpuinfo_max_freq");
+
+    Cpu {
+        brand,
+        cores,
+        threads,
+        performance_cores,
+        efficiency_cores,
+        current_mhz,
+        max_mhz,
+        temperature: temperature(root),
+    }
+}
+
+/// Parses a kernel CPU list such as `0-3,8,10-11` into `cpuN` directory names.
+///
+/// ```
+/// use symfetch::modules::cpu::parse_cpu_list;
+///
+/// assert_eq!(parse_cpu_list("0-2,5"), vec!["cpu0", "cpu1", "cpu2", "cpu5"]);
+/// ```
+pub fn parse_cpu_list(list: &str) -> Vec<String> {
+    list.trim()
+        .split(',')
+        .filter_map(|range| match range.split_once('-') {
+            Some((start, end)) => Some(start.parse::<usize>().ok()?..=end.parse().ok()?),
+            None => {
+                let cpu = range.parse::<usize>().ok()?;
+                Some(cpu..=cpu)
+            }
+        })
+        .flatten()
+        .map(|cpu| format!("cpu{cpu}"))
+        .collect()
+}
+
+fn logical_cpus(cpu_dir: &Path) -> Vec<String> {
+    if let Some(online) = read_trimmed(&cpu_dir.join("online")) {
+        return parse_cpu_list(&online);
+    }
+
+    let mut cpus: Vec<String> = fs::read_dir(cpu_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| {
+            name.strip_prefix("cpu")
+                .is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+        })
+        .collect();
+    cpus.sort();
+    cpus
+}
+
+/// counts distinct (package, core) pairs among `cpus`
+fn physical_cores(cpu_dir: &Path, cpus: &[String]) -> usize {
+    cpus.iter()
+        .filter_map(|cpu| {
+            let topology = cpu_dir.join(cpu).join("topology");
+            Some((
+                read_trimmed(&topology.join("physical_package_id"))?,
+                read_trimmed(&topology.join("core_id"))?,
+            ))
+        })
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+fn temperature(root: &Path) -> Option<f32> {
+    let hwmon = fs::read_dir(root.join("sys/class/hwmon"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|dir| {
+            read_trimmed(&dir.join("name")).is_some_and(|name| HWMON_SENSORS.contains(&&*name))
+        })
+        .find_map(|dir| read_trimmed(&dir.join("temp1_input")));
+
+    let millidegrees = hwmon.or_else(|| {
+        fs::read_dir(root.join("sys/class/thermal"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|dir| {
+                read_trimmed(&dir.join("type")).is_some_and(|kind| THERMAL_ZONES.contains(&&*kind))
+            })
+            .find_map(|dir| read_trimmed(&dir.join("temp")))
+    })?;
+
+    millidegrees
+        .parse::<f32>()
+        .ok()
+        .map(|millidegrees| millidegrees / 1000.0)
+}
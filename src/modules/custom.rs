@@ -0,0 +1,208 @@
+//! user defined lines produced by shell commands
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use regex::Regex;
+use serde_derive::Deserialize;
+
+use crate::util::path_utils::get_path;
+use crate::util::process::output_with_timeout;
+
+/// how the output of a custom command becomes the displayed value
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseMode {
+    /// first non-empty line
+    #[default]
+    FirstLine,
+    /// the whole output, lines joined with spaces
+    Output,
+    /// first capture group of `regex`, or the whole match without groups
+    Regex,
+}
+
+/// store parsed information from a "\[\[module.custom\]\]" entry
+///
+/// ```toml
+/// [[module.custom]]
+/// label = "On call"
+/// command = "curl -s https://oncall.example.com/now"
+/// # Optional: script = "~/.config/symfetch/oncall.sh"
+/// # Optional: timeout = 2
+/// # Optional: cache_ttl = 300
+/// # Optional: parse = "regex"
+/// # Optional: regex = "name: (\\w+)"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct CustomConfig {
+    /// label shown in front of the value, e.g. `On call`
+    pub label: String,
+
+    /// command run through `sh -c`
+    pub command: Option<String>,
+
+    /// executable run instead of `command`
+    pub script: Option<PathBuf>,
+
+    /// seconds after which the command is killed, defaults to 1
+    pub timeout: Option<u64>,
+
+    /// seconds the output is reused for instead of running the command again
+    pub cache_ttl: Option<u64>,
+
+    pub parse: Option<ParseMode>,
+
+    /// pattern used by the `regex` parse mode
+    pub regex: Option<String>,
+}
+
+/// the value of a custom line, or why it couldn't be produced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomLine {
+    pub label: String,
+    pub value: Result<String, String>,
+}
+
+/// Runs all custom commands concurrently.
+///
+/// # Arguments
+/// * `configs` - the `[[module.custom]]` entries
+/// * `cache_dir` - directory for cached outputs, usually `~/.cache/symfetch/custom`
+///
+/// ```
+/// use std::path::Path;
+/// use std::time::Instant;
+/// use symfetch::modules::custom::{probe, CustomConfig, ParseMode};
+///
+/// let configs = vec![
+///     CustomConfig {
+///         label: "Cluster".to_string(),
+///         command: Some("printf 'context: prod-eu\\n'".to_string()),
+///         parse: Some(ParseMode::Regex),
+///         regex: Some("context: (\\S+)".to_string()),
+///         ..Default::default()
+///     },
+///     CustomConfig {
+///         label: "Slow".to_string(),
+///         command: Some("sleep 5".to_string()),
+///         timeout: Some(1),
+///         ..Default::default()
+///     },
+///     CustomConfig {
+///         label: "Detached".to_string(),
+///         command: Some("sleep 5 & echo hi".to_string()),
+///         timeout: Some(1),
+///         ..Default::default()
+///     },
+/// ];
+/// let start = Instant::now();
+/// let lines = probe(&configs, Path::new("/nonexistent"));
+/// assert_eq!(lines[0].value.as_deref(), Ok("prod-eu"));
+/// assert_eq!(lines[1].value, Err("timed out after 1s".to_string()));
+/// assert_eq!(lines[2].value, Err("timed out after 1s".to_string()));
+/// assert!(start.elapsed().as_secs() < 3);
+/// ```
+pub fn probe(configs: &[CustomConfig], cache_dir: &Path) -> Vec<CustomLine> {
+    thread::scope(|scope| {
+        let handles: Vec<_> = configs
+            .iter()
+            .map(|config| scope.spawn(move || run(config, cache_dir)))
+            .collect();
+        handles
+            .into_iter()
+            .zip(configs)
+            .map(|(handle, config)| CustomLine {
+                label: config.label.clone(),
+                value: handle
+                    .join()
+                    .unwrap_or_else(|_| Err("command panicked".to_string())),
+            })
+            .collect()
+    })
+}
+
+fn run(config: &CustomConfig, cache_dir: &Path) -> Result<String, String> {
+    let mut command = match (&config.script, &config.command) {
+        (Some(script), _) => Command::new(get_path(script)),
+        (None, Some(command)) => {
+            let mut sh = Command::new("sh");
+            sh.args(["-c", command]);
+            sh
+        }
+        (None, None) => return Err("no command or script configured".to_string()),
+    };
+
+    let cache = config
+        .cache_ttl
+        .map(|ttl| (cache_dir.join(cache_key(config)), Duration::from_secs(ttl)));
+    let cached = cache.as_ref().and_then(|(path, ttl)| {
+        let age = fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())?;
+        (age < *ttl)
+            .then(|| fs::read_to_string(path).ok())
+            .flatten()
+    });
+
+    let output = match cached {
+        Some(output) => output,
+        None => {
+            let timeout = Duration::from_secs(config.timeout.unwrap_or(1));
+            let output = output_with_timeout(&mut command, timeout)?;
+            if let Some((path, _)) = &cache {
+                // a failing cache only costs the next run some time
+                let _ = fs::create_dir_all(cache_dir).and_then(|_| fs::write(path, &output));
+            }
+            output
+        }
+    };
+
+    parse(&output, config)
+}
+
+fn parse(output: &str, config: &CustomConfig) -> Result<String, String> {
+    let value = match config.parse.unwrap_or_default() {
+        ParseMode::FirstLine => output
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(str::to_string),
+        ParseMode::Output => {
+            let lines: Vec<&str> = output
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect();
+            (!lines.is_empty()).then(|| lines.join(" "))
+        }
+        ParseMode::Regex => {
+            let pattern = config
+                .regex
+                .as_deref()
+                .ok_or("parse mode regex needs a regex")?;
+            let regex = Regex::new(pattern).map_err(|err| err.to_string())?;
+            regex.captures(output).map(|captures| {
+                captures
+                    .get(1)
+                    .or_else(|| captures.get(0))
+                    .map_or(String::new(), |m| m.as_str().trim().to_string())
+            })
+        }
+    };
+    value.ok_or_else(|| "no output".to_string())
+}
+
+/// cached outputs are keyed by everything that affects them
+fn cache_key(config: &CustomConfig) -> String {
+    let mut hasher = DefaultHasher::new();
+    config.label.hash(&mut hasher);
+    config.command.hash(&mut hasher);
+    config.script.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
@@ -0,0 +1,211 @@
+//! desktop environment, window manager and display server detection
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::util::process::{self, ProcessInfo};
+
+/// process names of window managers and compositors and how they are displayed
+const WINDOW_MANAGERS: &[(&str, &str)] = &[
+    ("sway", "sway"),
+    ("Hyprland", "Hyprland"),
+    ("hyprland", "Hyprland"),
+    ("river", "river"),
+    ("niri", "niri"),
+    ("wayfire", "Wayfire"),
+    ("labwc", "labwc"),
+    ("dwl", "dwl"),
+    ("weston", "Weston"),
+    ("cage", "Cage"),
+    ("kwin_wayland", "KWin"),
+    ("kwin_x11", "KWin"),
+    ("kwin", "KWin"),
+    ("gnome-shell", "Mutter"),
+    ("mutter", "Mutter"),
+    ("muffin", "Muffin"),
+    ("cinnamon", "Muffin"),
+    ("marco", "Marco"),
+    ("xfwm4", "xfwm4"),
+    ("i3", "i3"),
+    ("bspwm", "bspwm"),
+    ("awesome", "awesome"),
+    ("dwm", "dwm"),
+    ("herbstluftwm", "herbstluftwm"),
+    ("openbox", "Openbox"),
+    ("fluxbox", "Fluxbox"),
+    ("icewm", "IceWM"),
+    ("qtile", "Qtile"),
+    ("spectrwm", "spectrwm"),
+    ("leftwm", "LeftWM"),
+    ("enlightenment", "Enlightenment"),
+    ("budgie-wm", "Budgie WM"),
+    ("fvwm", "FVWM"),
+    ("jwm", "JWM"),
+];
+
+/// process names that identify a desktop environment
+const DESKTOP_ENVIRONMENTS: &[(&str, &str)] = &[
+    ("gnome-shell", "GNOME"),
+    ("plasmashell", "KDE Plasma"),
+    ("xfce4-session", "Xfce"),
+    ("cinnamon-sessio", "Cinnamon"),
+    ("mate-session", "MATE"),
+    ("lxqt-session", "LXQt"),
+    ("lxsession", "LXDE"),
+    ("budgie-desktop", "Budgie"),
+    ("cosmic-session", "COSMIC"),
+    ("gala", "Pantheon"),
+];
+
+/// how the session is displayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayServer {
+    Wayland,
+    X11,
+    /// no graphical session, e.g. a virtual console or SSH
+    Tty,
+}
+
+impl fmt::Display for DisplayServer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DisplayServer::Wayland => write!(f, "Wayland"),
+            DisplayServer::X11 => write!(f, "X11"),
+            DisplayServer::Tty => write!(f, "tty"),
+        }
+    }
+}
+
+/// the graphical session symfetch is running in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Desktop {
+    pub desktop_environment: Option<String>,
+    pub window_manager: Option<String>,
+    pub display_server: DisplayServer,
+}
+
+impl Desktop {
+    /// Formats the window manager as `sway (Wayland)`.
+    pub fn format_wm(&self) -> String {
+        match &self.window_manager {
+            Some(wm) => format!("{wm} ({})", self.display_server),
+            None => format!("None ({})", self.display_server),
+        }
+    }
+}
+
+/// Detects the desktop environment, window manager and display server.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `proc/` and `tmp/`, `/` on a live system
+/// * `env` - environment lookup, usually [`std::env::var`]
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::modules::desktop::{probe, DisplayServer};
+///
+/// let env = |key: &str| match key {
+///     "XDG_RUNTIME_DIR" => Some("run/user/1000".to_string()),
+///     "WAYLAND_DISPLAY" => Some("wayland-1".to_string()),
+///     _ => None,
+/// };
+/// let desktop = probe(Path::new("tests/fixtures/root"), &env);
+/// assert_eq!(desktop.desktop_environment, None);
+/// assert_eq!(desktop.window_manager.as_deref(), Some("sway"));
+/// assert_eq!(desktop.display_server, DisplayServer::Wayland);
+/// ```
+pub fn probe(root: &Path, env: &dyn Fn(&str) -> Option<String>) -> Desktop {
+    let display_server = display_server(root, env);
+    if display_server == DisplayServer::Tty {
+        return Desktop {
+            desktop_environment: None,
+            window_manager: None,
+            display_server,
+        };
+    }
+
+    let processes = process::list(root);
+    let find = |known: &[(&str, &str)]| {
+        known.iter().find_map(|(name, display)| {
+            processes
+                .iter()
+                .any(|process: &ProcessInfo| process.name == *name || process.exe_name() == *name)
+                .then(|| display.to_string())
+        })
+    };
+
+    let desktop_environment = env("XDG_CURRENT_DESKTOP")
+        .or_else(|| env("DESKTOP_SESSION"))
+        .map(|desktop| normalize_desktop(&desktop))
+        .or_else(|| find(DESKTOP_ENVIRONMENTS))
+        // standalone window managers often set XDG_CURRENT_DESKTOP to their own name
+        .filter(|desktop| {
+            !WINDOW_MANAGERS
+                .iter()
+                .any(|(_, wm)| wm.eq_ignore_ascii_case(desktop))
+        });
+
+    Desktop {
+        desktop_environment,
+        window_manager: find(WINDOW_MANAGERS),
+        display_server,
+    }
+}
+
+/// `XDG_CURRENT_DESKTOP` may list several names, e.g. `ubuntu:GNOME`
+fn normalize_desktop(desktop: &str) -> String {
+    let name = desktop
+        .split(':')
+        .find(|name| !name.eq_ignore_ascii_case("ubuntu") && !name.eq_ignore_ascii_case("pop"))
+        .or_else(|| desktop.split(':').next())
+        .unwrap_or(desktop);
+    match name.to_lowercase().as_str() {
+        "kde" | "plasma" => "KDE Plasma".to_string(),
+        "gnome" | "gnome-classic" => "GNOME".to_string(),
+        "xfce" | "xfce4" => "Xfce".to_string(),
+        "x-cinnamon" | "cinnamon" => "Cinnamon".to_string(),
+        "mate" => "MATE".to_string(),
+        "lxqt" => "LXQt".to_string(),
+        "lxde" => "LXDE".to_string(),
+        "budgie" => "Budgie".to_string(),
+        _ => name.to_string(),
+    }
+}
+
+fn display_server(root: &Path, env: &dyn Fn(&str) -> Option<String>) -> DisplayServer {
+    match env("XDG_SESSION_TYPE").as_deref() {
+        Some("wayland") => return DisplayServer::Wayland,
+        Some("x11") => return DisplayServer::X11,
+        Some("tty") => return DisplayServer::Tty,
+        _ => {}
+    }
+
+    if let Some(display) = env("WAYLAND_DISPLAY") {
+        let socket = match PathBuf::from(&display) {
+            absolute if absolute.is_absolute() => absolute,
+            relative => PathBuf::from(env("XDG_RUNTIME_DIR").unwrap_or_default()).join(relative),
+        };
+        if root
+            .join(socket.strip_prefix("/").unwrap_or(&socket))
+            .exists()
+        {
+            return DisplayServer::Wayland;
+        }
+    }
+
+    if let Some(display) = env("DISPLAY") {
+        // `:0` is served through `/tmp/.X11-unix/X0`, `host:10.0` is a forwarded display
+        let local = display
+            .strip_prefix(':')
+            .and_then(|number| number.split('.').next())
+            .map(|number| {
+                root.join("tmp/.X11-unix")
+                    .join(format!("X{number}"))
+                    .exists()
+            });
+        if local.unwrap_or(true) {
+            return DisplayServer::X11;
+        }
+    }
+
+    DisplayServer::Tty
+}
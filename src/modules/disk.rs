@@ -0,0 +1,197 @@
+//! per-mount storage breakdown
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use glob::Pattern;
+use serde_derive::Deserialize;
+use sysinfo::Disks;
+
+/// filesystem types that never hold user data and are skipped unless
+/// explicitly listed in `include_fs`, the root mount is kept whatever its type
+const PSEUDO_FS: &[&str] = &[
+    "tmpfs",
+    "devtmpfs",
+    "overlay",
+    "squashfs",
+    "ramfs",
+    "proc",
+    "sysfs",
+    "efivarfs",
+    "autofs",
+    "fuse.portal",
+];
+
+/// store parsed information from the "\[module.disk\]" table
+///
+/// ```toml
+/// [module.disk]
+/// # Optional: show_mounts = true
+/// # Optional: include = ["/", "/home"]
+/// # Optional: exclude = ["/boot*"]
+/// # Optional: include_fs = ["ext4", "btrfs"]
+/// # Optional: exclude_fs = ["vfat"]
+/// # Optional: format = "{used} / {total} ({percent}%) - {fs}"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct DiskConfig {
+    /// list every mount on its own line in addition to the total
+    pub show_mounts: Option<bool>,
+
+    /// mountpoint globs to keep, everything is kept if empty
+    pub include: Option<Vec<String>>,
+
+    /// mountpoint globs to drop
+    pub exclude: Option<Vec<String>>,
+
+    /// filesystem types to keep, everything but pseudo filesystems is kept if empty
+    pub include_fs: Option<Vec<String>>,
+
+    /// filesystem types to drop
+    pub exclude_fs: Option<Vec<String>>,
+
+    /// template for the value of the line, see [`Template`](crate::util::template::Template)
+    pub format: Option<String>,
+}
+
+impl DiskConfig {
+    /// Checks that every `include` and `exclude` entry is a valid glob.
+    ///
+    /// ```
+    /// use symfetch::modules::disk::DiskConfig;
+    ///
+    /// let config = DiskConfig { exclude: Some(vec!["/boot[".to_string()]), ..Default::default() };
+    /// assert_eq!(
+    ///     config.validate().unwrap_err(),
+    ///     "[module.disk] exclude: invalid glob `/boot[`, invalid range pattern"
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), String> {
+        for (key, globs) in [("include", &self.include), ("exclude", &self.exclude)] {
+            for glob in globs.as_deref().unwrap_or_default() {
+                Pattern::new(glob).map_err(|err| {
+                    format!("[module.disk] {key}: invalid glob `{glob}`, {}", err.msg)
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// a single mounted filesystem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskEntry {
+    /// backing device, e.g. `/dev/nvme0n1p2`
+    pub device: String,
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+    /// total size in bytes
+    pub total: u64,
+    /// available space in bytes
+    pub available: u64,
+}
+
+impl DiskEntry {
+    /// used space in bytes
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.available)
+    }
+
+    /// used space as a percentage of the total
+    pub fn percent(&self) -> u64 {
+        (self.used() * 100).checked_div(self.total).unwrap_or(0)
+    }
+}
+
+/// Returns every mounted filesystem reported by the OS.
+pub fn probe() -> Vec<DiskEntry> {
+    Disks::new_with_refreshed_list()
+        .iter()
+        .map(|disk| DiskEntry {
+            device: disk.name().to_string_lossy().to_string(),
+            mount_point: disk.mount_point().to_path_buf(),
+            fs_type: disk.file_system().to_string_lossy().to_string(),
+            total: disk.total_space(),
+            available: disk.available_space(),
+        })
+        .collect()
+}
+
+/// Applies the include/exclude rules and drops duplicate mounts of the same device.
+///
+/// Bind mounts and btrfs subvolumes share their backing device, so only the
+/// entry with the shortest mountpoint is kept for each device. Pseudo filesystems
+/// are skipped, except at `/` where containers mount an overlay.
+///
+/// # Arguments
+/// * `entries` - mounts as returned by [`probe`]
+/// * `config` - filtering rules
+///
+/// # Returns
+/// * `Vec<DiskEntry>` - remaining mounts, sorted by mountpoint
+///
+/// ```
+/// use std::path::PathBuf;
+/// use symfetch::modules::disk::{filter, DiskConfig, DiskEntry};
+///
+/// let entry = |device: &str, mount: &str, fs: &str| DiskEntry {
+///     device: device.to_string(),
+///     mount_point: PathBuf::from(mount),
+///     fs_type: fs.to_string(),
+///     total: 100,
+///     available: 50,
+/// };
+/// let entries = vec![
+///     entry("/dev/sda1", "/home", "btrfs"),
+///     entry("/dev/sda1", "/", "btrfs"),
+///     entry("tmpfs", "/tmp", "tmpfs"),
+/// ];
+///
+/// let disks = filter(entries, &DiskConfig::default());
+/// assert_eq!(disks.len(), 1);
+/// assert_eq!(disks[0].mount_point, PathBuf::from("/"));
+///
+/// // the root of a container
+/// let entries = vec![entry("overlay", "/", "overlay"), entry("overlay", "/etc/hosts", "overlay")];
+/// let disks = filter(entries, &DiskConfig::default());
+/// assert_eq!(disks.len(), 1);
+/// assert_eq!(disks[0].fs_type, "overlay");
+/// ```
+pub fn filter(entries: Vec<DiskEntry>, config: &DiskConfig) -> Vec<DiskEntry> {
+    let include = patterns(config.include.as_deref());
+    let exclude = patterns(config.exclude.as_deref());
+    let include_fs = config.include_fs.as_deref().unwrap_or_default();
+    let exclude_fs = config.exclude_fs.as_deref().unwrap_or_default();
+
+    let mut kept: Vec<DiskEntry> = entries
+        .into_iter()
+        .filter(|entry| {
+            let mount = entry.mount_point.to_string_lossy();
+            let fs_ok = if include_fs.is_empty() {
+                mount == "/" || !PSEUDO_FS.contains(&entry.fs_type.as_str())
+            } else {
+                include_fs.contains(&entry.fs_type)
+            };
+            fs_ok
+                && !exclude_fs.contains(&entry.fs_type)
+                && (include.is_empty() || include.iter().any(|p| p.matches(&mount)))
+                && !exclude.iter().any(|p| p.matches(&mount))
+                && entry.total > 0
+        })
+        .collect();
+
+    // shortest mountpoint first so that it wins deduplication
+    kept.sort_by_key(|entry| entry.mount_point.as_os_str().len());
+    let mut seen = HashSet::new();
+    kept.retain(|entry| seen.insert(entry.device.clone()));
+    kept.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+    kept
+}
+
+/// invalid globs are rejected by [`DiskConfig::validate`] when the config is loaded
+fn patterns(globs: Option<&[String]>) -> Vec<Pattern> {
+    globs
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|glob| Pattern::new(glob).ok())
+        .collect()
+}
@@ -0,0 +1,141 @@
+//! host model, motherboard and BIOS from DMI
+use std::path::Path;
+
+use crate::util::fs_utils::read_trimmed;
+
+/// values OEMs leave in DMI fields they didn't bother to fill in
+const PLACEHOLDERS: &[&str] = &[
+    "to be filled by o.e.m.",
+    "to be filled by oem",
+    "default string",
+    "system product name",
+    "system version",
+    "system manufacturer",
+    "not applicable",
+    "not specified",
+    "type1productconfigid",
+    "o.e.m.",
+    "oem",
+    "none",
+    "n/a",
+    "x.x",
+    "0123456789",
+    "123456789",
+    "invalid",
+    "unknown",
+];
+
+/// hardware identity of the machine
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Host {
+    pub vendor: Option<String>,
+    pub product_name: Option<String>,
+    pub product_version: Option<String>,
+    pub board_vendor: Option<String>,
+    pub board_name: Option<String>,
+    pub bios_version: Option<String>,
+    pub bios_date: Option<String>,
+}
+
+impl Host {
+    /// Formats the model as vendor, name and version, skipping repeated words.
+    pub fn model(&self) -> Option<String> {
+        join(&[&self.vendor, &self.product_name, &self.product_version])
+    }
+
+    /// Formats the motherboard as vendor and name.
+    pub fn board(&self) -> Option<String> {
+        join(&[&self.board_vendor, &self.board_name])
+    }
+
+    /// Formats the BIOS as version and date.
+    pub fn bios(&self) -> Option<String> {
+        match (&self.bios_version, &self.bios_date) {
+            (Some(version), Some(date)) => Some(format!("{version} ({date})")),
+            (Some(version), None) => Some(version.clone()),
+            (None, Some(date)) => Some(date.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Reads `sys/class/dmi/id`, falling back to the device tree on ARM boards.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `sys/` and `proc/`, `/` on a live system
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::modules::host::probe;
+///
+/// let host = probe(Path::new("tests/fixtures/root"));
+/// assert_eq!(host.model().as_deref(), Some("Micro-Star International Co., Ltd. MS-7C56"));
+/// assert_eq!(
+///     host.board().as_deref(),
+///     Some("Micro-Star International Co., Ltd. B550-A PRO (MS-7C56)")
+/// );
+/// assert_eq!(host.bios().as_deref(), Some("A.D0 (02/08/2023)"));
+/// ```
+pub fn probe(root: &Path) -> Host {
+    let dmi = root.join("sys/class/dmi/id");
+    let read = |file: &str| read_trimmed(&dmi.join(file)).and_then(|value| sanitize(&value));
+
+    let host = Host {
+        vendor: read("sys_vendor"),
+        product_name: read("product_name"),
+        product_version: read("product_version"),
+        board_vendor: read("board_vendor"),
+        board_name: read("board_name"),
+        bios_version: read("bios_version"),
+        bios_date: read("bios_date"),
+    };
+    if host.model().is_some() {
+        return host;
+    }
+
+    // Raspberry Pi and other ARM boards have no DMI, e.g. "Raspberry Pi 4 Model B Rev 1.4"
+    let model = [
+        "proc/device-tree/model",
+        "sys/firmware/devicetree/base/model",
+    ]
+    .iter()
+    .find_map(|file| read_trimmed(&root.join(file)))
+    .map(|model| model.trim_end_matches('\0').to_string())
+    .and_then(|model| sanitize(&model));
+    Host {
+        product_name: model,
+        ..host
+    }
+}
+
+/// Drops OEM placeholder strings.
+///
+/// ```
+/// use symfetch::modules::host::sanitize;
+///
+/// assert_eq!(sanitize("To Be Filled By O.E.M."), None);
+/// assert_eq!(sanitize("ThinkPad X1 Carbon Gen 9").as_deref(), Some("ThinkPad X1 Carbon Gen 9"));
+/// ```
+pub fn sanitize(value: &str) -> Option<String> {
+    let value = value.trim();
+    let placeholder = value.is_empty()
+        || PLACEHOLDERS.contains(&value.to_lowercase().as_str())
+        || value
+            .chars()
+            .all(|c| c == '0' || c == 'x' || c == '.' || c == ' ');
+    (!placeholder).then(|| value.to_string())
+}
+
+/// joins the parts that aren't already contained in an earlier part
+fn join(parts: &[&Option<String>]) -> Option<String> {
+    let mut joined: Option<String> = None;
+    for part in parts.iter().copied().flatten() {
+        joined = match joined {
+            Some(joined) if joined.contains(part.as_str()) => Some(joined),
+            Some(joined) if part.starts_with(joined.as_str()) => Some(part.clone()),
+            Some(joined) => Some(format!("{joined} {part}")),
+            None => Some(part.clone()),
+        };
+    }
+    joined
+}
@@ -0,0 +1,169 @@
+//! init system, its version and failed systemd units
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_derive::Deserialize;
+
+use crate::modules::shell::parse_version;
+use crate::util::fs_utils::read_trimmed;
+
+/// `comm` of PID 1 and how the init system is displayed
+const INIT_SYSTEMS: &[(&str, &str)] = &[
+    ("systemd", "systemd"),
+    ("openrc-init", "OpenRC"),
+    ("runit", "runit"),
+    ("s6-svscan", "s6"),
+    ("dinit", "dinit"),
+    ("launchd", "launchd"),
+    ("shepherd", "GNU Shepherd"),
+    ("tini", "tini"),
+    ("docker-init", "tini"),
+    ("dumb-init", "dumb-init"),
+    ("catatonit", "catatonit"),
+];
+
+/// store parsed information from the "\[module.init\]" table
+///
+/// ```toml
+/// [module.init]
+/// # Optional: failed_units = true
+/// # Optional: systemctl = "/usr/bin/systemctl"
+/// # Optional: format = "{name}{?failed: ({failed} failed)}"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct InitConfig {
+    /// count failed systemd units, this runs `systemctl` once
+    pub failed_units: Option<bool>,
+
+    /// command used to list failed units, defaults to `systemctl`
+    pub systemctl: Option<PathBuf>,
+
+    /// template for the value of the line, see [`Template`](crate::util::template::Template)
+    pub format: Option<String>,
+}
+
+/// the init system / service manager running as PID 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Init {
+    pub name: String,
+    pub version: Option<String>,
+    /// number of failed units, only known for systemd
+    pub failed_units: Option<usize>,
+}
+
+impl Init {
+    /// Formats as `systemd 255 (2 failed units)`.
+    pub fn format(&self) -> String {
+        let mut line = self.name.clone();
+        if let Some(version) = &self.version {
+            line.push_str(&format!(" {version}"));
+        }
+        match self.failed_units {
+            Some(1) => line.push_str(" (1 failed unit)"),
+            Some(failed) if failed > 1 => line.push_str(&format!(" ({failed} failed units)")),
+            _ => {}
+        }
+        line
+    }
+}
+
+/// Detects the init system from `/proc/1/comm`.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `proc/` and `usr/`, `/` on a live system
+/// * `config` - whether and how to count failed units
+///
+/// ```
+/// use std::path::{Path, PathBuf};
+/// use symfetch::modules::init::{probe, InitConfig};
+///
+/// let config = InitConfig {
+///     failed_units: Some(true),
+///     systemctl: Some(PathBuf::from("tests/fixtures/bin/systemctl")),
+///     ..Default::default()
+/// };
+/// let init = probe(Path::new("tests/fixtures/root"), &config);
+/// assert_eq!(init.format(), "systemd 255 (2 failed units)");
+/// ```
+pub fn probe(root: &Path, config: &InitConfig) -> Init {
+    let comm = read_trimmed(&root.join("proc/1/comm")).unwrap_or_default();
+    let name = INIT_SYSTEMS
+        .iter()
+        .find(|(process, _)| *process == comm)
+        .map(|(_, display)| display.to_string())
+        // sysvinit, busybox and OpenRC all run as a plain `init`
+        .or_else(|| {
+            (comm == "init").then(|| {
+                if root.join("run/openrc").exists() {
+                    "OpenRC".to_string()
+                } else if root.join("run/runit").exists() {
+                    "runit".to_string()
+                } else {
+                    "SysVinit".to_string()
+                }
+            })
+        })
+        .unwrap_or_else(|| {
+            if comm.is_empty() {
+                "Unknown".to_string()
+            } else {
+                comm
+            }
+        });
+
+    let version = match name.as_str() {
+        "systemd" => systemd_version(root),
+        "OpenRC" => command_version("openrc", &["--version"]),
+        "dinit" => command_version("dinit", &["--version"]),
+        _ => None,
+    };
+
+    let failed_units = (name == "systemd" && config.failed_units.unwrap_or(true))
+        .then(|| {
+            let systemctl = config
+                .systemctl
+                .clone()
+                .unwrap_or_else(|| PathBuf::from("systemctl"));
+            failed_units(&systemctl)
+        })
+        .flatten();
+
+    Init {
+        name,
+        version,
+        failed_units,
+    }
+}
+
+/// the shared library is versioned, e.g. `libsystemd-shared-255.so`
+fn systemd_version(root: &Path) -> Option<String> {
+    ["usr/lib/systemd", "usr/lib64/systemd", "lib/systemd"]
+        .iter()
+        .flat_map(|dir| fs::read_dir(root.join(dir)).into_iter().flatten().flatten())
+        .find_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let version = name
+                .strip_prefix("libsystemd-shared-")?
+                .strip_suffix(".so")?;
+            Some(version.to_string())
+        })
+}
+
+fn command_version(command: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(command).args(args).output().ok()?;
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn failed_units(systemctl: &Path) -> Option<usize> {
+    let output = Command::new(systemctl)
+        .args(["--failed", "--no-legend", "--plain"])
+        .output()
+        .ok()?;
+    output.status.success().then(|| {
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count()
+    })
+}
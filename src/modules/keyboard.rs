@@ -0,0 +1,108 @@
+//! keyboard layout from the X11/Wayland and console configuration
+use std::fs;
+use std::path::Path;
+
+use crate::util::fs_utils::read_trimmed;
+
+/// configured keyboard layouts
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Keyboard {
+    /// XKB layouts, e.g. `us,de`
+    pub layout: Option<String>,
+    /// XKB variants, e.g. `,nodeadkeys`
+    pub variant: Option<String>,
+    /// virtual console keymap
+    pub keymap: Option<String>,
+}
+
+impl Keyboard {
+    /// Formats as `us, de (nodeadkeys)`, `None` if nothing is configured.
+    ///
+    /// ```
+    /// use symfetch::modules::keyboard::Keyboard;
+    ///
+    /// let keyboard = Keyboard {
+    ///     layout: Some("us,de".to_string()),
+    ///     variant: Some(",nodeadkeys".to_string()),
+    ///     keymap: Some("us".to_string()),
+    /// };
+    /// assert_eq!(keyboard.format().as_deref(), Some("us, de (nodeadkeys)"));
+    /// ```
+    pub fn format(&self) -> Option<String> {
+        let Some(layout) = &self.layout else {
+            return self.keymap.clone();
+        };
+
+        // variants pair up with layouts by position
+        let variants: Vec<&str> = self
+            .variant
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .collect();
+        let layouts: Vec<String> = layout
+            .split(',')
+            .enumerate()
+            .map(|(i, layout)| match variants.get(i).map(|v| v.trim()) {
+                Some(variant) if !variant.is_empty() => format!("{} ({variant})", layout.trim()),
+                _ => layout.trim().to_string(),
+            })
+            .collect();
+        let mut line = layouts.join(", ");
+
+        // only worth mentioning when the console differs from the graphical session
+        if let Some(keymap) = &self.keymap
+            && !layout.split(',').any(|layout| layout.trim() == keymap)
+        {
+            line.push_str(&format!(" [console: {keymap}]"));
+        }
+        Some(line)
+    }
+}
+
+/// Reads the keyboard configuration written by `localectl` or Debian's `keyboard-configuration`.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `etc/`, `/` on a live system
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::modules::keyboard::probe;
+///
+/// let keyboard = probe(Path::new("tests/fixtures/root"));
+/// assert_eq!(keyboard.format().as_deref(), Some("de (nodeadkeys) [console: de-latin1-nodeadkeys]"));
+/// ```
+pub fn probe(root: &Path) -> Keyboard {
+    let mut keyboard = Keyboard::default();
+
+    // written by systemd-localed: Option "XkbLayout" "de"
+    let xorg =
+        fs::read_to_string(root.join("etc/X11/xorg.conf.d/00-keyboard.conf")).unwrap_or_default();
+    let xorg_option = |key: &str| {
+        xorg.lines().find_map(|line| {
+            let mut fields = line.trim().strip_prefix("Option")?.split('"');
+            fields.nth(1).filter(|name| *name == key)?;
+            fields.nth(1).map(|value| value.to_string())
+        })
+    };
+    keyboard.layout = xorg_option("XkbLayout");
+    keyboard.variant = xorg_option("XkbVariant");
+
+    // shell style KEY="value" files
+    let shell_var = |file: &str, key: &str| {
+        let content = read_trimmed(&root.join(file))?;
+        content.lines().find_map(|line| {
+            let value = line.trim().strip_prefix(key)?.strip_prefix('=')?;
+            Some(value.trim_matches('"').to_string()).filter(|value| !value.is_empty())
+        })
+    };
+    if keyboard.layout.is_none() {
+        keyboard.layout = shell_var("etc/default/keyboard", "XKBLAYOUT")
+            .or_else(|| shell_var("etc/vconsole.conf", "XKBLAYOUT"));
+        keyboard.variant = shell_var("etc/default/keyboard", "XKBVARIANT")
+            .or_else(|| shell_var("etc/vconsole.conf", "XKBVARIANT"));
+    }
+    keyboard.keymap = shell_var("etc/vconsole.conf", "KEYMAP");
+
+    keyboard
+}
@@ -0,0 +1,74 @@
+//! 1/5/15-minute load averages
+use serde_derive::Deserialize;
+use sysinfo::System;
+
+/// store parsed information from the "\[module.load\]" table
+///
+/// ```toml
+/// [module.load]
+/// # Optional: normalize = false
+/// # Optional: format = "{one} {five} {fifteen}"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct LoadConfig {
+    /// divide the load averages by the number of logical cores, on by default
+    pub normalize: Option<bool>,
+
+    /// template for the value of the line, see [`Template`](crate::util::template::Template)
+    pub format: Option<String>,
+}
+
+/// load averages over the last 1, 5 and 15 minutes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Load {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+    /// number of logical cores the load is spread across
+    pub cores: usize,
+}
+
+impl Load {
+    /// 1-minute load relative to the number of cores, as a percentage
+    pub fn percent(&self) -> u64 {
+        (self.one * 100.0 / self.cores.max(1) as f64).round() as u64
+    }
+
+    /// Formats the three averages, optionally divided by the number of cores.
+    ///
+    /// ```
+    /// use symfetch::modules::load::Load;
+    ///
+    /// let load = Load { one: 2.0, five: 1.0, fifteen: 0.5, cores: 4 };
+    /// assert_eq!(load.format(false), "2.00 1.00 0.50");
+    /// assert_eq!(load.format(true), "0.50 0.25 0.12");
+    /// ```
+    pub fn format(&self, normalize: bool) -> String {
+        let [one, five, fifteen] = self.averages(normalize);
+        format!("{one:.2} {five:.2} {fifteen:.2}")
+    }
+
+    /// Returns the three averages, optionally divided by the number of cores.
+    pub fn averages(&self, normalize: bool) -> [f64; 3] {
+        let divisor = if normalize {
+            self.cores.max(1) as f64
+        } else {
+            1.0
+        };
+        [self.one, self.five, self.fifteen].map(|average| average / divisor)
+    }
+}
+
+/// Returns the current load averages.
+///
+/// # Arguments
+/// * `sys` - an already refreshed [`System`], used for the core count
+pub fn probe(sys: &System) -> Load {
+    let load = System::load_average();
+    Load {
+        one: load.one,
+        five: load.five,
+        fifteen: load.fifteen,
+        cores: sys.cpus().len(),
+    }
+}
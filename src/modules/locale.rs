@@ -0,0 +1,170 @@
+//! system locale and locale-aware rendering of the header date
+use std::path::Path;
+
+use chrono::{DateTime, Local};
+use serde_derive::Deserialize;
+
+use crate::util::fs_utils::read_trimmed;
+
+/// locale categories that override `LANG` for part of the output
+const CATEGORIES: &[&str] = &[
+    "LC_CTYPE",
+    "LC_NUMERIC",
+    "LC_TIME",
+    "LC_COLLATE",
+    "LC_MONETARY",
+    "LC_MESSAGES",
+    "LC_PAPER",
+    "LC_NAME",
+    "LC_ADDRESS",
+    "LC_TELEPHONE",
+    "LC_MEASUREMENT",
+];
+
+/// store parsed information from the "\[module.date\]" table
+///
+/// ```toml
+/// [module.date]
+/// # Optional: format = "%m/%d/%y %H:%M"
+/// # Optional: localized = false
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct DateConfig {
+    /// strftime format of the date in the header
+    pub format: Option<String>,
+
+    /// render month and day names in the `LC_TIME` locale
+    pub localized: Option<bool>,
+}
+
+/// the locale symfetch is running with
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Locale {
+    /// `LC_ALL` or `LANG`
+    pub lang: Option<String>,
+    /// `LC_*` categories that differ from `lang`
+    pub overrides: Vec<(String, String)>,
+}
+
+impl Locale {
+    /// Formats as `en_US.UTF-8 (LC_TIME=de_DE.UTF-8)`.
+    pub fn format(&self) -> String {
+        let mut line = self.lang.clone().unwrap_or_else(|| "C".to_string());
+        if !self.overrides.is_empty() {
+            let overrides: Vec<String> = self
+                .overrides
+                .iter()
+                .map(|(category, value)| format!("{category}={value}"))
+                .collect();
+            line.push_str(&format!(" ({})", overrides.join(", ")));
+        }
+        line
+    }
+
+    /// the locale dates are rendered in
+    pub fn time(&self) -> Option<&str> {
+        self.overrides
+            .iter()
+            .find(|(category, _)| category == "LC_TIME")
+            .map(|(_, value)| value.as_str())
+            .or(self.lang.as_deref())
+    }
+}
+
+/// Reads the locale from the environment, falling back to `/etc/locale.conf`.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `etc/`, `/` on a live system
+/// * `env` - environment lookup, usually [`std::env::var`]
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::modules::locale::probe;
+///
+/// let env = |key: &str| match key {
+///     "LANG" => Some("en_US.UTF-8".to_string()),
+///     "LC_TIME" => Some("de_DE.UTF-8".to_string()),
+///     _ => None,
+/// };
+/// let locale = probe(Path::new("tests/fixtures/root"), &env);
+/// assert_eq!(locale.format(), "en_US.UTF-8 (LC_TIME=de_DE.UTF-8)");
+///
+/// // without a locale in the environment the system default is used
+/// let locale = probe(Path::new("tests/fixtures/root"), &|_| None);
+/// assert_eq!(locale.format(), "de_DE.UTF-8");
+/// ```
+pub fn probe(root: &Path, env: &dyn Fn(&str) -> Option<String>) -> Locale {
+    let env = |key: &str| env(key).filter(|value| !value.is_empty());
+
+    // LC_ALL overrides every category
+    if let Some(all) = env("LC_ALL") {
+        return Locale {
+            lang: Some(all),
+            overrides: Vec::new(),
+        };
+    }
+
+    let locale_conf = ["etc/locale.conf", "etc/default/locale"]
+        .iter()
+        .find_map(|file| read_trimmed(&root.join(file)))
+        .unwrap_or_default();
+    let system = |key: &str| {
+        locale_conf.lines().find_map(|line| {
+            let value = line.strip_prefix(key)?.strip_prefix('=')?;
+            Some(value.trim_matches('"').to_string()).filter(|value| !value.is_empty())
+        })
+    };
+
+    let lang = env("LANG").or_else(|| system("LANG"));
+    let overrides = CATEGORIES
+        .iter()
+        .filter_map(|category| {
+            let value = env(category).or_else(|| system(category))?;
+            (Some(&value) != lang.as_ref()).then(|| (category.to_string(), value))
+        })
+        .collect();
+
+    Locale { lang, overrides }
+}
+
+/// Renders the header date according to `config`.
+///
+/// # Arguments
+/// * `datetime` - time to render
+/// * `config` - strftime format and whether to localize it
+/// * `locale` - locale such as `de_DE.UTF-8`, names stay English if it is unknown
+///
+/// ```
+/// use chrono::{Local, TimeZone};
+/// use symfetch::modules::locale::{format_date, DateConfig};
+///
+/// let datetime = Local.with_ymd_and_hms(2024, 3, 5, 14, 30, 0).unwrap();
+/// assert_eq!(format_date(&datetime, &DateConfig::default(), Some("de_DE.UTF-8")), "03/05/24 14:30");
+///
+/// let config = DateConfig {
+///     format: Some("%A, %e. %B".to_string()),
+///     localized: Some(true),
+/// };
+/// assert_eq!(format_date(&datetime, &config, Some("de_DE.UTF-8")), "Dienstag,  5. März");
+/// assert_eq!(format_date(&datetime, &config, None), "Tuesday,  5. March");
+/// ```
+pub fn format_date(
+    datetime: &DateTime<Local>,
+    config: &DateConfig,
+    locale: Option<&str>,
+) -> String {
+    let format = config.format.as_deref().unwrap_or("%m/%d/%y %H:%M");
+    let locale = locale
+        .filter(|_| config.localized.unwrap_or(true))
+        .and_then(chrono_locale);
+    match locale {
+        Some(locale) => datetime.format_localized(format, locale).to_string(),
+        None => datetime.format(format).to_string(),
+    }
+}
+
+/// `de_DE.UTF-8@euro` -> `de_DE`
+fn chrono_locale(locale: &str) -> Option<chrono::Locale> {
+    let name = locale.split(['.', '@']).next()?;
+    chrono::Locale::try_from(name).ok()
+}
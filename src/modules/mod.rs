@@ -0,0 +1,22 @@
+//! Collection of info modules shown next to the graphic
+pub mod battery;
+pub mod boot;
+pub mod colors;
+pub mod cpu;
+pub mod custom;
+pub mod desktop;
+pub mod disk;
+pub mod host;
+pub mod init;
+pub mod keyboard;
+pub mod load;
+pub mod locale;
+pub mod network;
+pub mod packages;
+pub mod processes;
+pub mod shell;
+pub mod swap;
+pub mod terminal;
+pub mod theme;
+pub mod timezone;
+pub mod virt;
@@ -0,0 +1,339 @@
+//! active network interfaces and local addresses
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use serde_derive::Deserialize;
+use sysinfo::Networks;
+
+use crate::util::fs_utils::read_trimmed;
+use crate::util::process::output_with_timeout;
+
+/// interface name prefixes used by VPN software
+const VPN_PREFIXES: &[&str] = &["wg", "tun", "tap", "tailscale", "zt", "ppp", "nordlynx"];
+
+/// `ARPHRD_*` link types of tunnel devices, see `include/uapi/linux/if_arp.h`
+const TUNNEL_TYPES: &[&str] = &["65534", "768", "769", "776", "778", "823"];
+
+/// how long `iw` may take to report the SSID
+const IW_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// store parsed information from the "\[module.network\]" table
+///
+/// ```toml
+/// [module.network]
+/// # Optional: hide_addresses = false
+/// # Optional: ipv6 = true
+/// # Optional: format = "{addresses}{?download: ↓ {download} ↑ {upload}}"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct NetworkConfig {
+    /// don't print any address, useful for screenshots
+    pub hide_addresses: Option<bool>,
+
+    /// include IPv6 addresses
+    pub ipv6: Option<bool>,
+
+    /// template for the value of the line, see [`Template`](crate::util::template::Template)
+    pub format: Option<String>,
+}
+
+/// what kind of link an interface is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkKind {
+    Wired,
+    Wireless {
+        ssid: Option<String>,
+        /// signal level in dBm
+        signal: Option<i32>,
+    },
+    /// VPN or other tunnel device
+    Tunnel,
+}
+
+/// a single active network interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interface {
+    pub name: String,
+    /// addresses in CIDR notation
+    pub addresses: Vec<String>,
+    /// link speed in Mb/s
+    pub speed: Option<u64>,
+    pub kind: LinkKind,
+    /// bytes received and sent since the interface came up
+    pub counters: Option<(u64, u64)>,
+    /// bytes received and sent per second, only known once [`throughput`] compared two probes
+    pub throughput: Option<(u64, u64)>,
+}
+
+impl Interface {
+    /// Formats the interface line according to `config`.
+    ///
+    /// ```
+    /// use symfetch::modules::network::{Interface, LinkKind, NetworkConfig};
+    ///
+    /// let interface = Interface {
+    ///     name: "wlan0".to_string(),
+    ///     addresses: vec!["192.168.1.20/24".to_string(), "fe80::1/64".to_string()],
+    ///     speed: None,
+    ///     kind: LinkKind::Wireless { ssid: Some("HomeNet".to_string()), signal: Some(-40) },
+    ///     counters: None,
+    ///     throughput: Some((1_572_864, 2048)),
+    /// };
+    /// assert_eq!(
+    ///     interface.format(&NetworkConfig::default()),
+    ///     "192.168.1.20/24, fe80::1/64 - Wi-Fi HomeNet (-40 dBm) - ↓ 1.5 MB/s ↑ 2.0 KB/s"
+    /// );
+    /// ```
+    pub fn format(&self, config: &NetworkConfig) -> String {
+        let mut parts = Vec::new();
+
+        let addresses = self.visible_addresses(config);
+        if !addresses.is_empty() {
+            parts.push(addresses.join(", "));
+        }
+
+        if let Some(speed) = self.speed {
+            parts.push(format!("{speed} Mb/s"));
+        }
+
+        match &self.kind {
+            LinkKind::Wired => {}
+            LinkKind::Wireless { ssid, signal } => {
+                let mut wifi = "Wi-Fi".to_string();
+                if let Some(ssid) = ssid {
+                    wifi.push_str(&format!(" {ssid}"));
+                }
+                if let Some(signal) = signal {
+                    wifi.push_str(&format!(" ({signal} dBm)"));
+                }
+                parts.push(wifi);
+            }
+            LinkKind::Tunnel => parts.push("VPN".to_string()),
+        }
+
+        if let Some((rx, tx)) = self.throughput {
+            parts.push(format!("↓ {} ↑ {}", format_rate(rx), format_rate(tx)));
+        }
+
+        parts.join(" - ")
+    }
+
+    /// Returns the addresses `config` allows to show.
+    pub fn visible_addresses(&self, config: &NetworkConfig) -> Vec<&str> {
+        if config.hide_addresses.unwrap_or(false) {
+            return Vec::new();
+        }
+        self.addresses
+            .iter()
+            .map(|address| address.as_str())
+            .filter(|address| config.ipv6.unwrap_or(true) || !address.contains(':'))
+            .collect()
+    }
+}
+
+/// Returns every interface that is up and has at least one address.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `sys/` and `proc/`, `/` on a live system
+pub fn probe(root: &Path) -> Vec<Interface> {
+    let mut interfaces = interfaces(root, &addresses());
+    for interface in &mut interfaces {
+        if let LinkKind::Wireless { ssid, .. } = &mut interface.kind {
+            *ssid = wifi_ssid(&interface.name);
+        }
+    }
+    interfaces
+}
+
+/// Probes the interfaces again without running `iw`, keeping the SSIDs found by
+/// [`probe`] and measuring the throughput since `previous`.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `sys/` and `proc/`, `/` on a live system
+/// * `previous` - the interfaces of the last probe
+/// * `elapsed` - time since the last probe
+pub fn refresh(root: &Path, previous: &[Interface], elapsed: Duration) -> Vec<Interface> {
+    let mut interfaces = interfaces(root, &addresses());
+    for interface in &mut interfaces {
+        if let LinkKind::Wireless { ssid, .. } = &mut interface.kind {
+            *ssid = previous
+                .iter()
+                .find(|previous| previous.name == interface.name)
+                .and_then(|previous| match &previous.kind {
+                    LinkKind::Wireless { ssid, .. } => ssid.clone(),
+                    _ => None,
+                });
+        }
+    }
+    throughput(previous, &mut interfaces, elapsed);
+    interfaces
+}
+
+/// addresses of every interface in CIDR notation, IPv4 first
+fn addresses() -> BTreeMap<String, Vec<String>> {
+    Networks::new_with_refreshed_list()
+        .iter()
+        .map(|(name, data)| {
+            let mut addresses: Vec<String> = data
+                .ip_networks()
+                .iter()
+                .map(|network| format!("{}/{}", network.addr, network.prefix))
+                .collect();
+            // IPv4 first, it's what people usually look for
+            addresses.sort_by_key(|address| address.contains(':'));
+            (name.clone(), addresses)
+        })
+        .collect()
+}
+
+/// Describes the interfaces below `sys/class/net` that are up and have an address.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `sys/` and `proc/`
+/// * `addresses` - addresses per interface name in CIDR notation
+pub fn interfaces(root: &Path, addresses: &BTreeMap<String, Vec<String>>) -> Vec<Interface> {
+    let signals = wireless_signals(root);
+
+    let mut names: Vec<String> = fs::read_dir(root.join("sys/class/net"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let dir = root.join("sys/class/net").join(&name);
+            let link_type = read_trimmed(&dir.join("type")).unwrap_or_default();
+
+            // tunnels usually report an "unknown" operstate
+            let operstate = read_trimmed(&dir.join("operstate"))?;
+            if name == "lo" || link_type == "772" || !matches!(&*operstate, "up" | "unknown") {
+                return None;
+            }
+            let addresses = addresses.get(&name).filter(|a| !a.is_empty())?.clone();
+
+            let kind = if dir.join("wireless").exists() || signals.contains_key(&name) {
+                LinkKind::Wireless {
+                    ssid: None,
+                    signal: signals.get(&name).copied(),
+                }
+            } else if TUNNEL_TYPES.contains(&&*link_type)
+                || dir.join("tun_flags").exists()
+                || VPN_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+            {
+                LinkKind::Tunnel
+            } else {
+                LinkKind::Wired
+            };
+
+            // virtual devices report -1 or fail to read
+            let speed = read_trimmed(&dir.join("speed"))
+                .and_then(|speed| speed.parse::<i64>().ok())
+                .filter(|speed| *speed > 0)
+                .map(|speed| speed as u64);
+
+            let statistic = |file: &str| {
+                read_trimmed(&dir.join("statistics").join(file))
+                    .and_then(|bytes| bytes.parse::<u64>().ok())
+            };
+            let counters = statistic("rx_bytes").zip(statistic("tx_bytes"));
+
+            Some(Interface {
+                name,
+                addresses,
+                speed,
+                kind,
+                counters,
+                throughput: None,
+            })
+        })
+        .collect()
+}
+
+/// Sets the throughput of every interface in `current` from how much its counters grew
+/// since `previous`, which was probed `elapsed` earlier.
+///
+/// ```
+/// use std::time::Duration;
+/// use symfetch::modules::network::{throughput, Interface, LinkKind};
+///
+/// let interface = |counters| Interface {
+///     name: "eth0".to_string(),
+///     addresses: Vec::new(),
+///     speed: None,
+///     kind: LinkKind::Wired,
+///     counters: Some(counters),
+///     throughput: None,
+/// };
+/// let mut current = vec![interface((6000, 1000))];
+/// throughput(&[interface((2000, 1000))], &mut current, Duration::from_secs(2));
+/// assert_eq!(current[0].throughput, Some((2000, 0)));
+/// ```
+pub fn throughput(previous: &[Interface], current: &mut [Interface], elapsed: Duration) {
+    let seconds = elapsed.as_secs_f64();
+    if seconds <= 0.0 {
+        return;
+    }
+    for interface in current {
+        let before = previous
+            .iter()
+            .find(|previous| previous.name == interface.name)
+            .and_then(|previous| previous.counters);
+        interface.throughput = before
+            .zip(interface.counters)
+            .map(|((rx0, tx0), (rx, tx))| {
+                // counters restart when an interface goes down
+                let rate =
+                    |before: u64, now: u64| (now.saturating_sub(before) as f64 / seconds) as u64;
+                (rate(rx0, rx), rate(tx0, tx))
+            });
+    }
+}
+
+/// formats bytes per second with a binary unit, e.g. `1.5 MB/s`
+pub fn format_rate(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B/s", "KB/s", "MB/s", "GB/s"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[0])
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// parses the signal level of every wireless interface from `/proc/net/wireless`
+fn wireless_signals(root: &Path) -> BTreeMap<String, i32> {
+    fs::read_to_string(root.join("proc/net/wireless"))
+        .unwrap_or_default()
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (name, stats) = line.split_once(':')?;
+            let level = stats.split_whitespace().nth(2)?;
+            let level = level.trim_end_matches('.').parse::<f32>().ok()?;
+            Some((name.trim().to_string(), level as i32))
+        })
+        .collect()
+}
+
+/// asks nl80211 through `iw` for the SSID, if `iw` is installed
+fn wifi_ssid(interface: &str) -> Option<String> {
+    let mut command = Command::new("iw");
+    command.args(["dev", interface, "link"]);
+    output_with_timeout(&mut command, IW_TIMEOUT)
+        .ok()?
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("SSID: "))
+        .map(|ssid| ssid.to_string())
+}
@@ -0,0 +1,216 @@
+//! installed package count across package managers
+//!
+//! Every count is read straight from the package manager's database so that
+//! no (slow) package manager command has to be spawned.
+use std::fs;
+use std::path::Path;
+
+use serde_derive::Deserialize;
+
+use crate::util::sqlite;
+
+/// store parsed information from the "\[module.packages\]" table
+///
+/// ```toml
+/// [module.packages]
+/// # Optional: exclude = ["cargo", "nix"]
+/// # Optional: format = "{total} ({managers})"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct PackagesConfig {
+    /// package managers that shouldn't be counted
+    pub exclude: Option<Vec<String>>,
+
+    /// template for the value of the line, see [`Template`](crate::util::template::Template)
+    pub format: Option<String>,
+}
+
+/// counts the packages of one package manager given the root and home directory
+type Counter = fn(&Path, &Path) -> usize;
+
+/// package managers in the order they are listed
+const MANAGERS: &[(&str, Counter)] = &[
+    ("dpkg", |root, _| dpkg(root)),
+    ("rpm", |root, _| rpm(root)),
+    ("pacman", |root, _| dirs(&root.join("var/lib/pacman/local"))),
+    ("apk", |root, _| apk(root)),
+    ("xbps", |root, _| xbps(root)),
+    ("portage", |root, _| portage(root)),
+    ("nix", nix),
+    ("flatpak", flatpak),
+    ("snap", |root, _| snap(root)),
+    ("brew", |root, _| brew(root)),
+    ("cargo", |_, home| cargo(home)),
+];
+
+/// number of packages installed through a single package manager
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageCount {
+    pub manager: &'static str,
+    pub count: usize,
+}
+
+/// Formats the counts as `1423 (dpkg), 37 (flatpak)`.
+pub fn format(counts: &[PackageCount]) -> String {
+    counts
+        .iter()
+        .map(|count| format!("{} ({})", count.count, count.manager))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Counts the installed packages of every package manager found on the system.
+///
+/// rpm packages are only counted from the sqlite database of rpm 4.16 and later. The
+/// Berkeley DB `Packages` of RHEL/CentOS 8 and the ndb `Packages.db` of openSUSE aren't
+/// supported, such systems get no rpm count.
+///
+/// # Arguments
+/// * `root` - filesystem root, `/` on a live system
+/// * `home` - home directory of the user, for per-user package managers
+/// * `config` - which package managers to skip
+///
+/// # Returns
+/// * `Vec<PackageCount>` - one entry per package manager with at least one package
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::modules::packages::{format, probe, PackagesConfig};
+///
+/// let counts = probe(
+///     Path::new("tests/fixtures/root"),
+///     Path::new("tests/fixtures/root/home/user"),
+///     &PackagesConfig::default(),
+/// );
+/// assert_eq!(
+///     format(&counts),
+///     "3 (dpkg), 3 (rpm), 2 (pacman), 1 (apk), 2 (flatpak), 1 (cargo)"
+/// );
+/// ```
+pub fn probe(root: &Path, home: &Path, config: &PackagesConfig) -> Vec<PackageCount> {
+    let exclude = config.exclude.as_deref().unwrap_or_default();
+    MANAGERS
+        .iter()
+        .filter(|(manager, _)| !exclude.iter().any(|excluded| excluded == manager))
+        .map(|(manager, count)| PackageCount {
+            manager,
+            count: count(root, home),
+        })
+        .filter(|count| count.count > 0)
+        .collect()
+}
+
+/// number of sub-directories of `dir`, ignoring hidden ones
+fn dirs(dir: &Path) -> usize {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            entry.path().is_dir() && !entry.file_name().to_string_lossy().starts_with('.')
+        })
+        .count()
+}
+
+fn dpkg(root: &Path) -> usize {
+    fs::read_to_string(root.join("var/lib/dpkg/status"))
+        .unwrap_or_default()
+        .lines()
+        // the first word is the wanted state, e.g. `install` or `hold`
+        .filter(|line| line.starts_with("Status: ") && line.ends_with(" ok installed"))
+        .count()
+}
+
+/// reads the sqlite database used since rpm 4.16, see [`probe`] for the other backends
+fn rpm(root: &Path) -> usize {
+    fs::File::open(root.join("var/lib/rpm/rpmdb.sqlite"))
+        .ok()
+        .and_then(|db| sqlite::count_rows(db, "Packages"))
+        .unwrap_or(0)
+}
+
+fn apk(root: &Path) -> usize {
+    fs::read_to_string(root.join("lib/apk/db/installed"))
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| line.starts_with("P:"))
+        .count()
+}
+
+fn xbps(root: &Path) -> usize {
+    fs::read_dir(root.join("var/db/xbps"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.starts_with("pkgdb-") && name.ends_with(".plist")
+        })
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .map(|plist| plist.matches("<key>pkgver</key>").count())
+        .sum()
+}
+
+fn portage(root: &Path) -> usize {
+    fs::read_dir(root.join("var/db/pkg"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|category| dirs(&category.path()))
+        .sum()
+}
+
+fn nix(root: &Path, home: &Path) -> usize {
+    [
+        root.join("nix/var/nix/profiles/default/manifest.json"),
+        home.join(".nix-profile/manifest.json"),
+    ]
+    .iter()
+    .filter_map(|manifest| fs::read_to_string(manifest).ok())
+    .filter_map(|manifest| serde_json::from_str::<serde_json::Value>(&manifest).ok())
+    // `elements` is a list up to version 2 of the manifest and a map since version 3
+    .map(|manifest| match &manifest["elements"] {
+        serde_json::Value::Array(elements) => elements.len(),
+        serde_json::Value::Object(elements) => elements.len(),
+        _ => 0,
+    })
+    .sum()
+}
+
+fn flatpak(root: &Path, home: &Path) -> usize {
+    let installations = [
+        root.join("var/lib/flatpak"),
+        home.join(".local/share/flatpak"),
+    ];
+    installations
+        .iter()
+        .flat_map(|installation| [installation.join("app"), installation.join("runtime")])
+        .map(|dir| dirs(&dir))
+        .sum()
+}
+
+fn snap(root: &Path) -> usize {
+    fs::read_dir(root.join("snap"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_dir() && entry.file_name() != "bin")
+        .count()
+}
+
+fn brew(root: &Path) -> usize {
+    ["home/linuxbrew/.linuxbrew", "opt/homebrew", "usr/local"]
+        .iter()
+        .map(|prefix| {
+            dirs(&root.join(prefix).join("Cellar")) + dirs(&root.join(prefix).join("Caskroom"))
+        })
+        .sum()
+}
+
+fn cargo(home: &Path) -> usize {
+    fs::read_to_string(home.join(".cargo/.crates.toml"))
+        .ok()
+        .and_then(|crates| crates.parse::<toml::Table>().ok())
+        .and_then(|crates| crates.get("v1")?.as_table().map(|v1| v1.len()))
+        .unwrap_or(0)
+}
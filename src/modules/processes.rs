@@ -0,0 +1,27 @@
+//! running process and thread counts
+use sysinfo::System;
+
+/// number of processes and threads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Processes {
+    pub processes: usize,
+    pub threads: usize,
+}
+
+/// Counts the processes and threads known to `sys`.
+///
+/// Threads are listed alongside processes on Linux, elsewhere only the main
+/// thread of every process is counted.
+pub fn probe(sys: &System) -> Processes {
+    let mut processes = 0;
+    let mut threads = 0;
+    for process in sys.processes().values() {
+        if process.thread_kind().is_some() {
+            threads += 1;
+        } else {
+            processes += 1;
+            threads += 1;
+        }
+    }
+    Processes { processes, threads }
+}
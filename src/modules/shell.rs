@@ -0,0 +1,156 @@
+//! detection of the shell symfetch was invoked from
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use serde_derive::Deserialize;
+
+use crate::util::process::{ProcessInfo, ancestors, output_with_timeout};
+
+/// process names of known shells
+const SHELLS: &[&str] = &[
+    "bash", "zsh", "fish", "nu", "dash", "sh", "ksh", "mksh", "oksh", "loksh", "tcsh", "csh",
+    "elvish", "xonsh", "pwsh", "ion", "osh", "ysh", "yash", "murex", "oil",
+];
+
+/// how long the shell may take to report its version
+const VERSION_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// store parsed information from the "\[module.shell\]" table
+///
+/// ```toml
+/// [module.shell]
+/// # Optional: version = true
+/// # Optional: format = "{name}{?version: {version}}"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct ShellConfig {
+    /// show the shell version, this runs the shell once
+    pub version: Option<bool>,
+
+    /// template for the value of the line, see [`Template`](crate::util::template::Template)
+    pub format: Option<String>,
+}
+
+/// the shell symfetch is running in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shell {
+    pub name: String,
+    /// executable of the running shell, if it could be resolved
+    pub exe: Option<PathBuf>,
+    pub version: Option<String>,
+}
+
+impl Shell {
+    /// Formats the shell as `fish 3.7.1`.
+    pub fn format(&self) -> String {
+        match &self.version {
+            Some(version) => format!("{} {version}", self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Detects the invoking shell, falling back to the login shell from `$SHELL`.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `proc/`, `/` on a live system
+/// * `env` - environment lookup, usually [`std::env::var`]
+/// * `config` - whether to look up the version
+pub fn probe(root: &Path, env: &dyn Fn(&str) -> Option<String>, config: &ShellConfig) -> Shell {
+    let mut shell = detect(root, std::process::id()).unwrap_or_else(|| {
+        let exe = env("SHELL").map(PathBuf::from);
+        Shell {
+            name: exe
+                .as_ref()
+                .and_then(|exe| exe.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            exe,
+            version: None,
+        }
+    });
+
+    if config.version.unwrap_or(true) {
+        shell.version = version(&shell);
+    }
+    shell
+}
+
+/// Returns the closest ancestor of `pid` that is a known shell.
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::modules::shell::detect;
+///
+/// // fish launched from a bash login shell
+/// let shell = detect(Path::new("tests/fixtures/root"), 300).unwrap();
+/// assert_eq!(shell.name, "fish");
+/// ```
+pub fn detect(root: &Path, pid: u32) -> Option<Shell> {
+    ancestors(root, pid).into_iter().find_map(|process| {
+        let name = shell_name(&process)?;
+        Some(Shell {
+            name,
+            exe: process.exe,
+            version: None,
+        })
+    })
+}
+
+/// login shells show up as `-bash`, some shells run through an interpreter
+fn shell_name(process: &ProcessInfo) -> Option<String> {
+    [process.exe_name(), process.name.clone()]
+        .into_iter()
+        .map(|name| name.trim_start_matches('-').to_string())
+        .find(|name| SHELLS.contains(&name.as_str()))
+}
+
+/// Asks the shell binary for its version.
+///
+/// Startup files are skipped, they may run arbitrary code or wait for input, and the
+/// shell is killed after [`VERSION_TIMEOUT`].
+fn version(shell: &Shell) -> Option<String> {
+    let exe = shell
+        .exe
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(&shell.name));
+    let args: &[&str] = match shell.name.as_str() {
+        // these only expose the exact version through a shell variable
+        "bash" => &["--norc", "--noprofile", "-c", "printf %s \"$BASH_VERSION\""],
+        "zsh" => &["-f", "-c", "printf %s \"$ZSH_VERSION\""],
+        "tcsh" | "csh" => &["-f", "-c", "echo $tcsh"],
+        // these have no way of reporting a plain version number
+        "dash" | "sh" | "ksh" | "mksh" | "oksh" | "loksh" => return None,
+        _ => &["--version"],
+    };
+
+    let mut command = Command::new(exe);
+    // a non-interactive bash sources `$BASH_ENV` even with `--norc`
+    command.args(args).env_remove("BASH_ENV").env_remove("ENV");
+    let output = output_with_timeout(&mut command, VERSION_TIMEOUT).ok()?;
+    parse_version(&output)
+}
+
+/// Extracts the first version number from the output of a shell.
+///
+/// ```
+/// use symfetch::modules::shell::parse_version;
+///
+/// assert_eq!(parse_version("fish, version 3.7.1").as_deref(), Some("3.7.1"));
+/// assert_eq!(parse_version("5.2.21(1)-release").as_deref(), Some("5.2.21"));
+/// assert_eq!(parse_version("v0.95.0").as_deref(), Some("0.95.0"));
+/// ```
+pub fn parse_version(output: &str) -> Option<String> {
+    output
+        .lines()
+        .next()?
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .map(|word| word.trim_start_matches('v'))
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()) && word.contains('.'))
+        .and_then(|word| {
+            word.split(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+                .next()
+        })
+        .map(|version| version.to_string())
+}
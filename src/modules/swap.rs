@@ -0,0 +1,56 @@
+//! swap usage with zram detection
+use std::path::Path;
+
+use sysinfo::System;
+
+/// swap usage of the whole system
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Swap {
+    /// used swap in bytes
+    pub used: u64,
+    /// total swap in bytes
+    pub total: u64,
+    /// whether (part of) the swap is backed by compressed RAM
+    pub zram: bool,
+}
+
+impl Swap {
+    /// used swap as a percentage of the total
+    pub fn percent(&self) -> u64 {
+        (self.used * 100).checked_div(self.total).unwrap_or(0)
+    }
+}
+
+/// Returns the swap usage reported by `sys`.
+///
+/// # Arguments
+/// * `sys` - an already refreshed [`System`]
+/// * `root` - filesystem root used to look up `/proc/swaps`
+pub fn probe(sys: &System, root: &Path) -> Swap {
+    Swap {
+        used: sys.used_swap(),
+        total: sys.total_swap(),
+        zram: swap_devices(root)
+            .iter()
+            .any(|device| device.starts_with("/dev/zram")),
+    }
+}
+
+/// Returns the devices and files listed in `/proc/swaps`.
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::modules::swap::swap_devices;
+///
+/// let devices = swap_devices(Path::new("tests/fixtures/root"));
+/// assert_eq!(devices, vec!["/dev/zram0", "/swapfile"]);
+/// ```
+pub fn swap_devices(root: &Path) -> Vec<String> {
+    std::fs::read_to_string(root.join("proc/swaps"))
+        .unwrap_or_default()
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|device| device.to_string())
+        .collect()
+}
@@ -0,0 +1,197 @@
+//! terminal emulator detection through the process tree
+use std::path::Path;
+use std::process::Command;
+
+use serde_derive::Deserialize;
+
+use crate::util::process::{ProcessInfo, ancestors};
+
+/// process names of known terminal emulators and how they are displayed
+const TERMINALS: &[(&str, &str)] = &[
+    ("kitty", "kitty"),
+    ("alacritty", "Alacritty"),
+    ("wezterm-gui", "WezTerm"),
+    ("wezterm", "WezTerm"),
+    ("ghostty", "Ghostty"),
+    ("foot", "foot"),
+    ("footclient", "foot"),
+    ("gnome-terminal-", "GNOME Terminal"),
+    ("gnome-terminal-server", "GNOME Terminal"),
+    ("kgx", "GNOME Console"),
+    ("konsole", "Konsole"),
+    ("xfce4-terminal", "Xfce Terminal"),
+    ("tilix", "Tilix"),
+    ("terminator", "Terminator"),
+    ("terminology", "Terminology"),
+    ("lxterminal", "LXTerminal"),
+    ("mate-terminal", "MATE Terminal"),
+    ("qterminal", "QTerminal"),
+    ("urxvt", "urxvt"),
+    ("rxvt", "rxvt"),
+    ("xterm", "xterm"),
+    ("st", "st"),
+    ("contour", "Contour"),
+    ("rio", "Rio"),
+    ("iTerm2", "iTerm2"),
+    ("Terminal", "Apple Terminal"),
+    ("code", "VS Code"),
+    ("Hyper", "Hyper"),
+    ("tabby", "Tabby"),
+    ("warp", "Warp"),
+];
+
+/// store parsed information from the "\[module.terminal\]" table
+///
+/// ```toml
+/// [module.terminal]
+/// # Optional: version = true
+/// # Optional: format = "{name}{?version: {version}}"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct TerminalConfig {
+    /// show the emulator version when it is cheaply available
+    pub version: Option<bool>,
+
+    /// template for the value of the line, see [`Template`](crate::util::template::Template)
+    pub format: Option<String>,
+}
+
+/// the terminal symfetch is running in
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Terminal {
+    /// emulator name, or `$TERM` if the emulator couldn't be identified
+    pub name: String,
+    pub version: Option<String>,
+    /// running inside a tmux session
+    pub tmux: bool,
+    /// running over SSH, in which case the emulator is on the remote end
+    pub ssh: bool,
+}
+
+impl Terminal {
+    /// Formats the terminal line according to `config`.
+    ///
+    /// ```
+    /// use symfetch::modules::terminal::{Terminal, TerminalConfig};
+    ///
+    /// let terminal = Terminal {
+    ///     name: "kitty".to_string(),
+    ///     version: Some("0.35.2".to_string()),
+    ///     tmux: true,
+    ///     ssh: false,
+    /// };
+    /// assert_eq!(terminal.format(&TerminalConfig::default()), "tmux in kitty 0.35.2");
+    /// ```
+    pub fn format(&self, config: &TerminalConfig) -> String {
+        let mut line = String::new();
+        if self.tmux {
+            line.push_str("tmux in ");
+        }
+        if self.ssh {
+            line.push_str("SSH session");
+        } else {
+            line.push_str(&self.name);
+            if config.version.unwrap_or(true)
+                && let Some(version) = &self.version
+            {
+                line.push_str(&format!(" {version}"));
+            }
+        }
+        line
+    }
+}
+
+/// Detects the terminal emulator of the current process.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `proc/`, `/` on a live system
+/// * `env` - environment lookup, usually [`std::env::var`]
+pub fn probe(root: &Path, env: &dyn Fn(&str) -> Option<String>) -> Terminal {
+    let tmux = env("TMUX").is_some();
+
+    // inside tmux our ancestors end at the tmux server, the emulator is a parent of the client
+    let start = if tmux {
+        tmux_client_pid().unwrap_or(std::process::id())
+    } else {
+        std::process::id()
+    };
+
+    let mut terminal = detect(root, start, env);
+    terminal.tmux = tmux;
+    terminal
+}
+
+/// Walks up from `pid` until a known terminal emulator or `sshd` is found.
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::modules::terminal::detect;
+///
+/// let terminal = detect(Path::new("tests/fixtures/root"), 300, &|_| None);
+/// assert_eq!(terminal.name, "kitty");
+/// ```
+pub fn detect(root: &Path, pid: u32, env: &dyn Fn(&str) -> Option<String>) -> Terminal {
+    let process = ProcessInfo::read(root, pid).into_iter();
+    for process in process.chain(ancestors(root, pid)) {
+        let exe = process.exe_name();
+        if exe == "sshd" || process.name == "sshd" {
+            return Terminal {
+                name: "SSH session".to_string(),
+                ssh: true,
+                ..Default::default()
+            };
+        }
+
+        let known = TERMINALS
+            .iter()
+            .find(|(name, _)| *name == exe || *name == process.name);
+        if let Some((_, display)) = known {
+            return Terminal {
+                name: display.to_string(),
+                version: version(display, env),
+                ..Default::default()
+            };
+        }
+    }
+
+    // no emulator in the tree, e.g. it runs as a service under a different user
+    if env("SSH_CONNECTION").is_some() || env("SSH_TTY").is_some() {
+        return Terminal {
+            name: "SSH session".to_string(),
+            ssh: true,
+            ..Default::default()
+        };
+    }
+    Terminal {
+        name: env("TERM_PROGRAM")
+            .or_else(|| env("TERM"))
+            .unwrap_or_else(|| "Unknown".to_string()),
+        version: env("TERM_PROGRAM_VERSION"),
+        ..Default::default()
+    }
+}
+
+/// versions that terminal emulators export to their children
+fn version(terminal: &str, env: &dyn Fn(&str) -> Option<String>) -> Option<String> {
+    match terminal {
+        // e.g. 230804 for 23.08.04
+        "Konsole" => env("KONSOLE_VERSION")
+            .filter(|version| version.len() == 6 && version.bytes().all(|b| b.is_ascii_digit()))
+            .map(|v| format!("{}.{}.{}", &v[0..2], &v[2..4], &v[4..6])),
+        // VTE based terminals only export the VTE version, e.g. 7600 for 0.76.0
+        "GNOME Terminal" | "GNOME Console" | "Tilix" | "Xfce Terminal" | "Terminator" => {
+            env("VTE_VERSION")
+                .and_then(|version| version.parse::<u32>().ok())
+                .map(|v| format!("VTE {}.{}.{}", v / 10000, (v / 100) % 100, v % 100))
+        }
+        _ => env("TERM_PROGRAM_VERSION"),
+    }
+}
+
+fn tmux_client_pid() -> Option<u32> {
+    let output = Command::new("tmux")
+        .args(["display-message", "-p", "#{client_pid}"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
@@ -0,0 +1,187 @@
+//! GTK/Qt widget theme, icon theme, cursor theme and font
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::util::ini;
+
+/// a setting together with the toolkit it was read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolkitValue {
+    /// `GTK2`, `GTK3`, `GTK4`, `Qt` or `XCursor`
+    pub toolkit: &'static str,
+    pub value: String,
+}
+
+/// appearance settings of the desktop, one entry per toolkit that sets them
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Themes {
+    pub theme: Vec<ToolkitValue>,
+    pub icons: Vec<ToolkitValue>,
+    pub cursor: Vec<ToolkitValue>,
+    pub font: Vec<ToolkitValue>,
+}
+
+/// Groups equal values, e.g. `Adwaita-dark [GTK3/4], Breeze [Qt]`.
+///
+/// ```
+/// use symfetch::modules::theme::{format, ToolkitValue};
+///
+/// let values = vec![
+///     ToolkitValue { toolkit: "GTK3", value: "Adwaita-dark".to_string() },
+///     ToolkitValue { toolkit: "GTK4", value: "Adwaita-dark".to_string() },
+///     ToolkitValue { toolkit: "Qt", value: "Breeze".to_string() },
+/// ];
+/// assert_eq!(format(&values), "Adwaita-dark [GTK3/4], Breeze [Qt]");
+/// ```
+pub fn format(values: &[ToolkitValue]) -> String {
+    let mut groups: Vec<(&str, Vec<&str>)> = Vec::new();
+    for value in values {
+        match groups.iter_mut().find(|(v, _)| *v == value.value) {
+            Some((_, toolkits)) => toolkits.push(value.toolkit),
+            None => groups.push((&value.value, vec![value.toolkit])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(value, toolkits)| {
+            // GTK2, GTK3 -> GTK2/3
+            let mut label = toolkits[0].to_string();
+            for toolkit in &toolkits[1..] {
+                match toolkit.strip_prefix("GTK") {
+                    Some(version) if label.starts_with("GTK") => {
+                        label.push('/');
+                        label.push_str(version);
+                    }
+                    _ => {
+                        label.push('/');
+                        label.push_str(toolkit);
+                    }
+                }
+            }
+            format!("{value} [{label}]")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Reads the appearance settings of GTK2/3/4, Qt (KDE, qt5ct, qt6ct) and XCursor.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `usr/share/icons`, `/` on a live system
+/// * `home` - home directory of the user
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::modules::theme::{format, probe};
+///
+/// let themes = probe(Path::new("tests/fixtures/root"), Path::new("tests/fixtures/root/home/user"));
+/// assert_eq!(format(&themes.theme), "Adwaita [GTK2], Adwaita-dark [GTK3/4], Breeze [Qt]");
+/// assert_eq!(format(&themes.icons), "Papirus Dark [GTK3/4], breeze-dark [Qt]");
+/// assert_eq!(format(&themes.cursor), "Bibata-Modern-Ice [GTK3/XCursor]");
+/// assert_eq!(format(&themes.font), "Cantarell 11 [GTK2/3], Noto Sans 10 [Qt]");
+/// ```
+pub fn probe(root: &Path, home: &Path) -> Themes {
+    let config = home.join(".config");
+    let mut themes = Themes::default();
+    let push = |values: &mut Vec<ToolkitValue>, toolkit, value: Option<String>| {
+        if let Some(value) = value {
+            values.push(ToolkitValue { toolkit, value });
+        }
+    };
+
+    // GTK2 uses its own `key = "value"` format without sections
+    let gtk2 = fs::read_to_string(home.join(".gtkrc-2.0")).unwrap_or_default();
+    let gtk2 = |key: &str| {
+        gtk2.lines().find_map(|line| {
+            let (k, value) = line.split_once('=')?;
+            let value = value.trim().trim_matches('"');
+            (k.trim() == key && !value.is_empty()).then(|| value.to_string())
+        })
+    };
+    push(&mut themes.theme, "GTK2", gtk2("gtk-theme-name"));
+    push(&mut themes.icons, "GTK2", gtk2("gtk-icon-theme-name"));
+    push(&mut themes.cursor, "GTK2", gtk2("gtk-cursor-theme-name"));
+    push(&mut themes.font, "GTK2", gtk2("gtk-font-name"));
+
+    for (toolkit, dir) in [("GTK3", "gtk-3.0"), ("GTK4", "gtk-4.0")] {
+        let settings =
+            fs::read_to_string(config.join(dir).join("settings.ini")).unwrap_or_default();
+        let get = |key| ini::get(&settings, "Settings", key);
+        push(&mut themes.theme, toolkit, get("gtk-theme-name"));
+        push(&mut themes.icons, toolkit, get("gtk-icon-theme-name"));
+        push(&mut themes.cursor, toolkit, get("gtk-cursor-theme-name"));
+        push(&mut themes.font, toolkit, get("gtk-font-name"));
+    }
+
+    // KDE first, then the qt5ct/qt6ct tools used outside of Plasma
+    let kdeglobals = fs::read_to_string(config.join("kdeglobals")).unwrap_or_default();
+    let kcminputrc = fs::read_to_string(config.join("kcminputrc")).unwrap_or_default();
+    let qtct = ["qt6ct/qt6ct.conf", "qt5ct/qt5ct.conf"]
+        .iter()
+        .find_map(|file| fs::read_to_string(config.join(file)).ok())
+        .unwrap_or_default();
+    push(
+        &mut themes.theme,
+        "Qt",
+        ini::get(&kdeglobals, "KDE", "widgetStyle")
+            .or_else(|| ini::get(&kdeglobals, "General", "widgetStyle"))
+            .or_else(|| ini::get(&qtct, "Appearance", "style")),
+    );
+    push(
+        &mut themes.icons,
+        "Qt",
+        ini::get(&kdeglobals, "Icons", "Theme")
+            .or_else(|| ini::get(&qtct, "Appearance", "icon_theme")),
+    );
+    push(
+        &mut themes.cursor,
+        "Qt",
+        ini::get(&kcminputrc, "Mouse", "cursorTheme"),
+    );
+    push(
+        &mut themes.font,
+        "Qt",
+        ini::get(&kdeglobals, "General", "font")
+            .or_else(|| ini::get(&qtct, "Fonts", "general"))
+            .map(|font| qt_font(&font)),
+    );
+
+    // the cursor X applications fall back to
+    let default_cursor = icon_dirs(root, home)
+        .iter()
+        .filter_map(|dir| fs::read_to_string(dir.join("default/index.theme")).ok())
+        .find_map(|index| ini::get(&index, "Icon Theme", "Inherits"))
+        .map(|inherits| inherits.split(',').next().unwrap_or_default().to_string());
+    push(&mut themes.cursor, "XCursor", default_cursor);
+
+    // icon theme directories are named by id, show the human readable name instead
+    for icons in &mut themes.icons {
+        if let Some(name) = icon_dirs(root, home)
+            .iter()
+            .filter_map(|dir| fs::read_to_string(dir.join(&icons.value).join("index.theme")).ok())
+            .find_map(|index| ini::get(&index, "Icon Theme", "Name"))
+        {
+            icons.value = name;
+        }
+    }
+
+    themes
+}
+
+fn icon_dirs(root: &Path, home: &Path) -> Vec<PathBuf> {
+    vec![
+        home.join(".local/share/icons"),
+        home.join(".icons"),
+        root.join("usr/share/icons"),
+    ]
+}
+
+/// Qt serializes fonts as `family,size,...`
+fn qt_font(font: &str) -> String {
+    let mut parts = font.split(',');
+    match (parts.next(), parts.next()) {
+        (Some(family), Some(size)) => format!("{family} {size}"),
+        _ => font.to_string(),
+    }
+}
@@ -0,0 +1,43 @@
+//! timezone of the system
+use std::fs;
+use std::path::Path;
+
+use crate::util::fs_utils::read_trimmed;
+
+/// Determines the timezone name from `$TZ`, the `/etc/localtime` symlink or `/etc/timezone`.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `etc/`, `/` on a live system
+/// * `env` - environment lookup, usually [`std::env::var`]
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::modules::timezone::probe;
+///
+/// let root = Path::new("tests/fixtures/root");
+/// assert_eq!(probe(root, &|_| None).as_deref(), Some("Europe/Berlin"));
+///
+/// let env = |key: &str| (key == "TZ").then(|| ":America/New_York".to_string());
+/// assert_eq!(probe(root, &env).as_deref(), Some("America/New_York"));
+/// ```
+pub fn probe(root: &Path, env: &dyn Fn(&str) -> Option<String>) -> Option<String> {
+    // glibc allows a leading colon, e.g. `TZ=:Europe/Berlin`
+    if let Some(tz) = env("TZ") {
+        let tz = tz.trim_start_matches(':');
+        let tz = tz.split_once("zoneinfo/").map_or(tz, |(_, name)| name);
+        if !tz.is_empty() {
+            return Some(tz.to_string());
+        }
+    }
+
+    fs::read_link(root.join("etc/localtime"))
+        .ok()
+        .and_then(|target| {
+            let target = target.to_string_lossy();
+            target
+                .split_once("zoneinfo/")
+                .map(|(_, name)| name.trim_start_matches("posix/").to_string())
+        })
+        .or_else(|| read_trimmed(&root.join("etc/timezone")))
+        .filter(|name| !name.is_empty())
+}
@@ -0,0 +1,223 @@
+//! container and hypervisor detection
+use std::fs;
+use std::path::Path;
+
+use crate::util::fs_utils::read_trimmed;
+
+/// CPUID hypervisor vendor signatures and how they are displayed
+const CPUID_VENDORS: &[(&str, &str)] = &[
+    ("KVMKVMKVM", "KVM"),
+    ("TCGTCGTCGTCG", "QEMU"),
+    ("VMwareVMware", "VMware"),
+    ("Microsoft Hv", "Hyper-V"),
+    ("XenVMMXenVMM", "Xen"),
+    ("VBoxVBoxVBox", "VirtualBox"),
+    ("bhyve bhyve ", "bhyve"),
+    (" lrpepyh  vr", "Parallels"),
+    ("ACRNACRNACRN", "ACRN"),
+    ("QNXQVMBSQG", "QNX"),
+];
+
+/// DMI vendor or product names of virtual machines
+const DMI_VENDORS: &[(&str, &str)] = &[
+    ("QEMU", "QEMU"),
+    ("KVM", "KVM"),
+    ("VMware", "VMware"),
+    ("VirtualBox", "VirtualBox"),
+    ("innotek GmbH", "VirtualBox"),
+    ("Xen", "Xen"),
+    ("Parallels", "Parallels"),
+    ("Amazon EC2", "KVM"),
+    ("Google Compute Engine", "KVM"),
+    ("BHYVE", "bhyve"),
+];
+
+/// values of the `container` variable set by container managers for PID 1
+const CONTAINER_VARS: &[(&str, &str)] = &[
+    ("docker", "Docker"),
+    ("podman", "Podman"),
+    ("lxc", "LXC"),
+    ("lxc-libvirt", "LXC"),
+    ("systemd-nspawn", "systemd-nspawn"),
+    ("oci", "OCI"),
+    ("wsl", "WSL"),
+    ("flatpak", "Flatpak"),
+];
+
+/// what symfetch is running inside of
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Virtualization {
+    pub container: Option<String>,
+    pub hypervisor: Option<String>,
+}
+
+impl Virtualization {
+    /// Formats as `Docker in KVM`, `None` on bare metal.
+    pub fn format(&self) -> Option<String> {
+        match (&self.container, &self.hypervisor) {
+            (Some(container), Some(hypervisor)) => Some(format!("{container} in {hypervisor}")),
+            (Some(name), None) | (None, Some(name)) => Some(name.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Detects containers and hypervisors on the running system.
+///
+/// # Arguments
+/// * `root` - filesystem root, `/` on a live system
+/// * `env` - environment lookup, usually [`std::env::var`]
+pub fn probe(root: &Path, env: &dyn Fn(&str) -> Option<String>) -> Virtualization {
+    detect(root, env, cpuid_vendor())
+}
+
+/// Detects containers and hypervisors from files below `root`.
+///
+/// # Arguments
+/// * `root` - filesystem root
+/// * `env` - environment lookup
+/// * `cpuid_vendor` - hypervisor signature from CPUID leaf `0x40000000`
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::modules::virt::detect;
+///
+/// let root = Path::new("tests/fixtures/root");
+/// let virt = detect(root, &|_| None, Some("KVMKVMKVM".to_string()));
+/// assert_eq!(virt.format().as_deref(), Some("Podman in KVM"));
+///
+/// let pod = detect(root, &|key| (key == "KUBERNETES_SERVICE_HOST").then(|| "10.0.0.1".to_string()), None);
+/// assert_eq!(pod.format().as_deref(), Some("Kubernetes pod (Podman)"));
+/// ```
+pub fn detect(
+    root: &Path,
+    env: &dyn Fn(&str) -> Option<String>,
+    cpuid_vendor: Option<String>,
+) -> Virtualization {
+    Virtualization {
+        container: container(root, env),
+        hypervisor: hypervisor(root, cpuid_vendor),
+    }
+}
+
+fn container(root: &Path, env: &dyn Fn(&str) -> Option<String>) -> Option<String> {
+    let environ = fs::read(root.join("proc/1/environ")).unwrap_or_default();
+    let environ = String::from_utf8_lossy(&environ);
+    let cgroup = fs::read_to_string(root.join("proc/1/cgroup")).unwrap_or_default();
+
+    let runtime = environ
+        .split('\0')
+        .find_map(|var| var.strip_prefix("container="))
+        .map(|name| {
+            CONTAINER_VARS
+                .iter()
+                .find(|(var, _)| *var == name)
+                .map_or(name.to_string(), |(_, display)| display.to_string())
+        })
+        .or_else(|| {
+            root.join("run/.containerenv")
+                .exists()
+                .then(|| "Podman".to_string())
+        })
+        .or_else(|| {
+            root.join(".dockerenv")
+                .exists()
+                .then(|| "Docker".to_string())
+        })
+        .or_else(|| {
+            root.join(".flatpak-info")
+                .exists()
+                .then(|| "Flatpak".to_string())
+        })
+        .or_else(|| {
+            [("docker", "Docker"), ("libpod", "Podman"), ("lxc", "LXC")]
+                .iter()
+                .find(|(name, _)| cgroup.contains(name))
+                .map(|(_, display)| display.to_string())
+        })
+        .or_else(|| {
+            let release = read_trimmed(&root.join("proc/sys/kernel/osrelease"))?;
+            if release.contains("WSL2") {
+                Some("WSL2".to_string())
+            } else {
+                release
+                    .to_lowercase()
+                    .contains("microsoft")
+                    .then(|| "WSL".to_string())
+            }
+        });
+
+    let kubernetes = env("KUBERNETES_SERVICE_HOST").is_some()
+        || cgroup.contains("kubepods")
+        || root
+            .join("var/run/secrets/kubernetes.io/serviceaccount")
+            .exists();
+    match (kubernetes, runtime) {
+        (true, Some(runtime)) => Some(format!("Kubernetes pod ({runtime})")),
+        (true, None) => Some("Kubernetes pod".to_string()),
+        (false, runtime) => runtime,
+    }
+}
+
+fn hypervisor(root: &Path, cpuid_vendor: Option<String>) -> Option<String> {
+    // Firecracker identifies itself through the OEM ID of its ACPI tables
+    if let Ok(dsdt) = fs::read(root.join("sys/firmware/acpi/tables/DSDT"))
+        && dsdt.get(10..16) == Some(b"FIRECK")
+    {
+        return Some("Firecracker".to_string());
+    }
+
+    let dmi = |file: &str| read_trimmed(&root.join("sys/class/dmi/id").join(file));
+    let dmi_vendor = [dmi("sys_vendor"), dmi("product_name")]
+        .into_iter()
+        .flatten()
+        .find_map(|value| {
+            if value == "Virtual Machine" {
+                // Microsoft Corporation / Virtual Machine
+                return Some("Hyper-V".to_string());
+            }
+            DMI_VENDORS
+                .iter()
+                .find(|(vendor, _)| value.contains(vendor))
+                .map(|(_, display)| display.to_string())
+        });
+
+    let cpuid = cpuid_vendor.and_then(|vendor| {
+        CPUID_VENDORS
+            .iter()
+            .find(|(signature, _)| vendor.starts_with(signature))
+            .map(|(_, display)| display.to_string())
+    });
+
+    cpuid.or(dmi_vendor).or_else(|| {
+        (read_trimmed(&root.join("sys/hypervisor/type")).as_deref() == Some("xen"))
+            .then(|| "Xen".to_string())
+    })
+}
+
+/// reads the hypervisor signature from CPUID leaf `0x40000000`
+#[cfg(target_arch = "x86_64")]
+fn cpuid_vendor() -> Option<String> {
+    use std::arch::x86_64::__cpuid;
+
+    // bit 31 of ECX in leaf 1 is reserved for hypervisors to announce themselves
+    let features = __cpuid(1);
+    if features.ecx & (1 << 31) == 0 {
+        return None;
+    }
+    let leaf = __cpuid(0x4000_0000);
+    let bytes: Vec<u8> = [leaf.ebx, leaf.ecx, leaf.edx]
+        .iter()
+        .flat_map(|register| register.to_le_bytes())
+        .collect();
+    Some(
+        String::from_utf8_lossy(&bytes)
+            .trim_end_matches('\0')
+            .to_string(),
+    )
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn cpuid_vendor() -> Option<String> {
+    None
+}
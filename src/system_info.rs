@@ -1,27 +1,162 @@
 use chrono::{DateTime, Local};
 use colored::*;
 use std::env;
-use sysinfo::{Disks, System};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sysinfo::{MINIMUM_CPU_UPDATE_INTERVAL, ProcessRefreshKind, ProcessesToUpdate, System};
 
+use crate::config_handler::{Config, ModuleConfig};
+use crate::layout::{self, LayoutConfig};
+use crate::modules::battery::{self, PowerSupply};
+use crate::modules::boot::{self, Boot};
+use crate::modules::colors;
+use crate::modules::cpu::{self, Cpu};
+use crate::modules::custom::{self, CustomLine};
+use crate::modules::desktop::{self, Desktop};
+use crate::modules::disk::{self, DiskConfig, DiskEntry};
+use crate::modules::host::{self, Host};
+use crate::modules::init::{self, Init};
+use crate::modules::keyboard::{self, Keyboard};
+use crate::modules::load::{self, Load};
+use crate::modules::locale::{self, Locale};
+use crate::modules::network::{self, Interface, LinkKind};
+use crate::modules::packages::{self, PackageCount};
+use crate::modules::processes::{self, Processes};
+use crate::modules::shell::{self, Shell};
+use crate::modules::swap::{self, Swap};
+use crate::modules::terminal::{self, Terminal};
+use crate::modules::theme::{self, Themes};
+use crate::modules::timezone;
+use crate::modules::virt::{self, Virtualization};
+use crate::util::template::Template;
+use crate::util::usage::UsageConfig;
+
+/// names of the modules producing info lines, as used by `[[layout.group]]`
+pub const MODULE_NAMES: &[&str] = &[
+    "os",
+    "host",
+    "virtualization",
+    "packages",
+    "uptime",
+    "init",
+    "boot",
+    "shell",
+    "displays",
+    "de",
+    "wm",
+    "terminal",
+    "theme",
+    "font",
+    "locale",
+    "timezone",
+    "keyboard",
+    "cpu",
+    "cpu_usage",
+    "gpu",
+    "memory",
+    "swap",
+    "load",
+    "processes",
+    "battery",
+    "network",
+    "storage",
+    "disk",
+    "custom",
+    "colors",
+];
+
+/// placeholders available in the `format` string of each module
+pub const FORMAT_FIELDS: &[(&str, &[&str])] = &[
+    ("os", &["name", "kernel"]),
+    ("host", &["model", "board", "bios"]),
+    ("packages", &["total", "managers"]),
+    ("uptime", &["days", "hours", "minutes", "since"]),
+    ("init", &["name", "version", "failed"]),
+    ("boot", &["mode", "secure_boot", "bootloader"]),
+    ("shell", &["name", "version"]),
+    ("de", &["name"]),
+    ("wm", &["name", "server"]),
+    ("terminal", &["name", "version"]),
+    ("theme", &["theme", "icons", "cursor"]),
+    ("locale", &["lang", "overrides"]),
+    ("timezone", &["name", "offset"]),
+    ("keyboard", &["layout", "variant", "keymap"]),
+    (
+        "cpu",
+        &["brand", "cores", "threads", "frequency", "temperature"],
+    ),
+    ("memory", &["used", "total", "percent"]),
+    ("swap", &["used", "total", "percent", "zram"]),
+    ("load", &["one", "five", "fifteen", "percent"]),
+    ("processes", &["processes", "threads"]),
+    ("battery", &["name", "capacity", "status", "time", "health"]),
+    ("storage", &["used", "total", "percent"]),
+    (
+        "disk",
+        &["mount", "device", "used", "total", "percent", "fs"],
+    ),
+    (
+        "network",
+        &[
+            "name",
+            "addresses",
+            "speed",
+            "ssid",
+            "signal",
+            "download",
+            "upload",
+        ],
+    ),
+];
+
+#[derive(Clone)]
 pub struct SystemInfo {
     pub user: String,
     pub hostname: String,
     pub datetime: DateTime<Local>,
-    pub os_info: String,
-    pub uptime: String,
-    pub shell: String,
+    pub os_name: String,
+    pub kernel: String,
+    pub host: Host,
+    pub virtualization: Virtualization,
+    pub packages: Vec<PackageCount>,
+    /// seconds since boot
+    pub uptime: u64,
+    pub init: Init,
+    pub boot: Boot,
+    pub shell: Shell,
     pub displays: String,
-    pub window_manager: String,
-    pub terminal: String,
+    pub desktop: Desktop,
+    pub terminal: Terminal,
+    pub themes: Themes,
     pub font: String,
-    pub cpu: String,
+    pub locale: Locale,
+    pub timezone: Option<String>,
+    pub keyboard: Keyboard,
+    pub cpu: Cpu,
+    pub cpu_usage: Option<u64>,
     pub gpu: String,
-    pub memory: String,
-    pub storage: String,
+    /// used memory in bytes
+    pub memory_used: u64,
+    /// total memory in bytes
+    pub memory_total: u64,
+    pub swap: Swap,
+    pub load: Load,
+    pub processes: Processes,
+    /// used space of all listed disks in bytes
+    pub storage_used: u64,
+    /// total space of all listed disks in bytes
+    pub storage_total: u64,
+    pub disks: Vec<DiskEntry>,
+    pub power: PowerSupply,
+    pub network: Vec<Interface>,
+    pub custom: Vec<CustomLine>,
+    pub module: ModuleConfig,
+    pub usage: UsageConfig,
+    pub layout: LayoutConfig,
 }
 
 impl SystemInfo {
-    pub fn new() -> Self {
+    pub fn new(config: &Config) -> Self {
         let mut sys = System::new_all();
         sys.refresh_all();
 
@@ -30,91 +165,154 @@ impl SystemInfo {
         let datetime = Local::now();
 
         // OS and Kernel info
-        let os_info = format!(
-            "{} {}",
-            System::long_os_version().unwrap_or_else(|| "Unknown".to_string()),
-            System::kernel_version().unwrap_or_else(|| "Unknown".to_string())
-        );
+        let os_name = System::long_os_version().unwrap_or_else(|| "Unknown".to_string());
+        let kernel = System::kernel_version().unwrap_or_else(|| "Unknown".to_string());
+
+        let root = Path::new("/");
+        let home = env::var("HOME").map(PathBuf::from).unwrap_or_default();
+        let packages = packages::probe(root, &home, &config.module.packages);
+        let host = host::probe(root);
+        let virtualization = virt::probe(root, &|key| env::var(key).ok());
 
         // Uptime
-        let uptime_secs = System::uptime();
-        let uptime = format!(
-            "{}d {}h {}m",
-            uptime_secs / 86400,
-            (uptime_secs % 86400) / 3600,
-            (uptime_secs % 3600) / 60
-        );
+        let uptime = System::uptime();
+
+        let init = init::probe(root, &config.module.init);
+        let boot = boot::probe(root, System::boot_time());
 
         // Shell
-        let shell = env::var("SHELL")
-            .unwrap_or_else(|_| "Unknown".to_string())
-            .split('/')
-            .next_back()
-            .unwrap_or("Unknown")
-            .to_string();
+        let shell = shell::probe(root, &|key| env::var(key).ok(), &config.module.shell);
 
         // TODO: Displays detection
         let displays = "1".to_string();
 
-        let window_manager = env::var("XDG_CURRENT_DESKTOP")
-            .or_else(|_| env::var("DESKTOP_SESSION"))
-            .unwrap_or_else(|_| "Unknown".to_string());
+        let desktop = desktop::probe(root, &|key| env::var(key).ok());
 
         // Terminal
-        let terminal = env::var("TERM").unwrap_or_else(|_| "Unknown".to_string());
+        let terminal = terminal::probe(root, &|key| env::var(key).ok());
+
+        // Theme, icons, cursor and font
+        let themes = theme::probe(root, &home);
+        let font = if themes.font.is_empty() {
+            "Unknown".to_string()
+        } else {
+            theme::format(&themes.font)
+        };
 
-        // TODO: Font detection
-        let font = "Unknown".to_string();
+        // Locale, timezone and keyboard layout
+        let locale = locale::probe(root, &|key| env::var(key).ok());
+        let timezone = timezone::probe(root, &|key| env::var(key).ok());
+        let keyboard = keyboard::probe(root);
 
         // CPU
-        let cpu_info = if let Some(cpu) = sys.cpus().first() {
-            format!("{} ({} cores)", cpu.brand().trim(), sys.cpus().len())
+        let cpu = cpu::probe(&sys, root);
+
+        // CPU usage needs two samples taken some time apart
+        let cpu_usage = if config.usage.cpu.unwrap_or(false) {
+            std::thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+            sys.refresh_cpu_usage();
+            Some(sys.global_cpu_usage().round() as u64)
         } else {
-            "Unknown".to_string()
+            None
         };
 
         // TODO: GPU detection
         let gpu = "Unknown".to_string();
 
         // Memory
-        let total_memory = sys.total_memory();
-        let used_memory = sys.used_memory();
-        let memory = format!(
-            "{}MB / {}MB",
-            used_memory / 1024 / 1024,
-            total_memory / 1024 / 1024
-        );
+        let memory_total = sys.total_memory();
+        let memory_used = sys.used_memory();
 
-        // Storage
-        let mut total_storage = 0;
-        let mut used_storage = 0;
-        let disks = Disks::new_with_refreshed_list();
-        for disk in disks.iter() {
-            total_storage += disk.total_space();
-            used_storage += disk.total_space() - disk.available_space();
-        }
-        let storage = format!(
-            "{}GB / {}GB",
-            used_storage / 1024 / 1024 / 1024,
-            total_storage / 1024 / 1024 / 1024
-        );
+        let swap = swap::probe(&sys, root);
+        let load = load::probe(&sys);
+        let processes = processes::probe(&sys);
+        let power = battery::probe(root);
+        let network = network::probe(root);
+
+        // Custom commands
+        let cache_dir = env::var("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| home.join(".cache"))
+            .join("symfetch/custom");
+        let custom = custom::probe(&config.module.custom, &cache_dir);
+
+        let (disks, storage_used, storage_total) = storage(&config.module.disk);
 
         SystemInfo {
             user,
             hostname,
             datetime,
-            os_info,
+            os_name,
+            kernel,
+            host,
+            virtualization,
+            packages,
             uptime,
+            init,
+            boot,
             shell,
             displays,
-            window_manager,
+            desktop,
             terminal,
+            themes,
             font,
-            cpu: cpu_info,
+            locale,
+            timezone,
+            keyboard,
+            cpu,
+            cpu_usage,
             gpu,
-            memory,
-            storage,
+            memory_used,
+            memory_total,
+            swap,
+            load,
+            processes,
+            storage_used,
+            storage_total,
+            disks,
+            power,
+            network,
+            custom,
+            module: config.module.clone(),
+            usage: config.usage.clone(),
+            layout: config.layout.clone(),
+        }
+    }
+
+    /// Probes the modules that change while running again, everything else is kept.
+    ///
+    /// # Arguments
+    /// No external command is run, the SSID of a Wi-Fi network is kept from the first probe.
+    ///
+    /// # Arguments
+    /// * `sys` - kept between refreshes so CPU usage covers the time since the last one
+    /// * `elapsed` - time since the last probe, used for network throughput
+    pub fn refresh(&mut self, sys: &mut System, elapsed: Duration) {
+        sys.refresh_memory();
+        sys.refresh_cpu_usage();
+        // only the list is needed for the counts, not what every process uses
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+
+        let root = Path::new("/");
+        self.datetime = Local::now();
+        self.uptime = System::uptime();
+        if self.usage.cpu.unwrap_or(false) {
+            self.cpu_usage = Some(sys.global_cpu_usage().round() as u64);
         }
+        self.memory_total = sys.total_memory();
+        self.memory_used = sys.used_memory();
+        self.swap = swap::probe(sys, root);
+        self.load = load::probe(sys);
+        self.processes = processes::probe(sys);
+        self.power = battery::probe(root);
+
+        self.network = network::refresh(root, &self.network, elapsed);
+
+        (self.disks, self.storage_used, self.storage_total) = storage(&self.module.disk);
     }
 
     pub fn as_vec(&self) -> Vec<String> {
@@ -124,28 +322,572 @@ impl SystemInfo {
             "{}@{} ({})",
             self.user.bold().cyan(),
             self.hostname.bold().cyan(),
-            self.datetime.format("%m/%d/%y %H:%M").to_string().dimmed()
+            locale::format_date(&self.datetime, &self.module.date, self.locale.time()).dimmed()
         ));
 
         lines.push(String::new());
 
-        lines.push(format!("{} {}", "OS:".bold().yellow(), self.os_info));
-        lines.push(format!("{} {}", "Uptime:".bold().yellow(), self.uptime));
-        lines.push(format!("{} {}", "Shell:".bold().yellow(), self.shell));
-        lines.push(format!("{} {}", "Displays:".bold().yellow(), self.displays));
-        lines.push(format!("{} {}", "WM:".bold().yellow(), self.window_manager));
-        lines.push(format!("{} {}", "Terminal:".bold().yellow(), self.terminal));
-        lines.push(format!("{} {}", "Font:".bold().yellow(), self.font));
-        lines.push(format!("{} {}", "CPU:".bold().yellow(), self.cpu));
-        lines.push(format!("{} {}", "GPU:".bold().yellow(), self.gpu));
-        lines.push(format!("{} {}", "Memory:".bold().yellow(), self.memory));
-        lines.push(format!("{} {}", "Storage:".bold().yellow(), self.storage));
+        let module_lines = self.module_lines();
+        debug_assert!(
+            module_lines
+                .iter()
+                .all(|(module, _)| MODULE_NAMES.contains(module)),
+            "module lines must be tagged with one of MODULE_NAMES"
+        );
+        if self.layout.group.is_empty() {
+            lines.extend(module_lines.into_iter().map(|(_, line)| line));
+        } else {
+            lines.extend(layout::group_lines(&module_lines, &self.layout));
+        }
+        lines
+    }
+
+    /// Returns the info lines below the header, each tagged with the module that produced it.
+    fn module_lines(&self) -> Vec<(&'static str, String)> {
+        let mut lines = Vec::new();
+
+        let os = self.templated(
+            "os",
+            &[
+                ("name", Some(self.os_name.clone())),
+                ("kernel", Some(self.kernel.clone())),
+            ],
+            || format!("{} {}", self.os_name, self.kernel),
+        );
+        lines.push(("os", format!("{} {}", "OS:".bold().yellow(), os)));
+        let host = [
+            ("model", self.host.model()),
+            ("board", self.host.board()),
+            ("bios", self.host.bios()),
+        ];
+        // a format merges the three lines into one
+        let host_lines = match self.module.host.format {
+            Some(_) => vec![("Host:", Some(self.templated("host", &host, String::new)))],
+            None => vec![
+                ("Host:", self.host.model()),
+                ("Board:", self.host.board()),
+                ("BIOS:", self.host.bios()),
+            ],
+        };
+        for (label, value) in host_lines {
+            if let Some(value) = value.filter(|value| !value.is_empty()) {
+                lines.push(("host", format!("{} {}", label.bold().yellow(), value)));
+            }
+        }
+        if let Some(virtualization) = self.virtualization.format() {
+            lines.push((
+                "virtualization",
+                format!("{} {}", "Virtualization:".bold().yellow(), virtualization),
+            ));
+        }
+        if !self.packages.is_empty() {
+            let total: usize = self.packages.iter().map(|count| count.count).sum();
+            let packages = self.templated(
+                "packages",
+                &[
+                    ("total", Some(total.to_string())),
+                    ("managers", Some(packages::format(&self.packages))),
+                ],
+                || packages::format(&self.packages),
+            );
+            lines.push((
+                "packages",
+                format!("{} {}", "Packages:".bold().yellow(), packages),
+            ));
+        }
+        let (days, hours, minutes) = (
+            self.uptime / 86400,
+            (self.uptime % 86400) / 3600,
+            (self.uptime % 3600) / 60,
+        );
+        let since = self
+            .boot
+            .boot_time
+            .map(|boot_time| boot_time.format("%Y-%m-%d %H:%M").to_string());
+        let uptime = self.templated(
+            "uptime",
+            &[
+                ("days", Some(days.to_string())),
+                ("hours", Some(hours.to_string())),
+                ("minutes", Some(minutes.to_string())),
+                ("since", since.clone()),
+            ],
+            || match &since {
+                Some(since) => format!("{days}d {hours}h {minutes}m (since {since})"),
+                None => format!("{days}d {hours}h {minutes}m"),
+            },
+        );
+        lines.push((
+            "uptime",
+            format!("{} {}", "Uptime:".bold().yellow(), uptime),
+        ));
+        let init = self.templated(
+            "init",
+            &[
+                ("name", Some(self.init.name.clone())),
+                ("version", self.init.version.clone()),
+                (
+                    "failed",
+                    self.init
+                        .failed_units
+                        .filter(|failed| *failed > 0)
+                        .map(|failed| failed.to_string()),
+                ),
+            ],
+            || self.init.format(),
+        );
+        lines.push(("init", format!("{} {}", "Init:".bold().yellow(), init)));
+        let boot = self.templated(
+            "boot",
+            &[
+                (
+                    "mode",
+                    Some(if self.boot.uefi { "UEFI" } else { "BIOS" }.to_string()),
+                ),
+                (
+                    "secure_boot",
+                    self.boot
+                        .secure_boot
+                        .map(|on| if on { "on" } else { "off" }.to_string()),
+                ),
+                ("bootloader", self.boot.bootloader.clone()),
+            ],
+            || self.boot.format(),
+        );
+        lines.push(("boot", format!("{} {}", "Boot:".bold().yellow(), boot)));
+        let shell = self.templated(
+            "shell",
+            &[
+                ("name", Some(self.shell.name.clone())),
+                ("version", self.shell.version.clone()),
+            ],
+            || self.shell.format(),
+        );
+        lines.push(("shell", format!("{} {}", "Shell:".bold().yellow(), shell)));
+        lines.push((
+            "displays",
+            format!("{} {}", "Displays:".bold().yellow(), self.displays),
+        ));
+        if let Some(desktop_environment) = &self.desktop.desktop_environment {
+            let de = self.templated("de", &[("name", Some(desktop_environment.clone()))], || {
+                desktop_environment.clone()
+            });
+            lines.push(("de", format!("{} {}", "DE:".bold().yellow(), de)));
+        }
+        let wm = self.templated(
+            "wm",
+            &[
+                ("name", self.desktop.window_manager.clone()),
+                ("server", Some(self.desktop.display_server.to_string())),
+            ],
+            || self.desktop.format_wm(),
+        );
+        lines.push(("wm", format!("{} {}", "WM:".bold().yellow(), wm)));
+        let terminal = self.templated(
+            "terminal",
+            &[
+                ("name", Some(self.terminal.name.clone())),
+                ("version", self.terminal.version.clone()),
+            ],
+            || self.terminal.format(&self.module.terminal),
+        );
+        lines.push((
+            "terminal",
+            format!("{} {}", "Terminal:".bold().yellow(), terminal),
+        ));
+        let themes = [
+            ("Theme:", "theme", &self.themes.theme),
+            ("Icons:", "icons", &self.themes.icons),
+            ("Cursor:", "cursor", &self.themes.cursor),
+        ];
+        // a format merges the three lines into one
+        if self.module.theme.format.is_some() {
+            let values: Vec<_> = themes
+                .iter()
+                .map(|(_, field, values)| {
+                    (
+                        *field,
+                        Some(theme::format(values)).filter(|value| !value.is_empty()),
+                    )
+                })
+                .collect();
+            let theme = self.templated("theme", &values, String::new);
+            if !theme.is_empty() {
+                lines.push(("theme", format!("{} {}", "Theme:".bold().yellow(), theme)));
+            }
+        } else {
+            for (label, _, values) in themes {
+                if !values.is_empty() {
+                    lines.push((
+                        "theme",
+                        format!("{} {}", label.bold().yellow(), theme::format(values)),
+                    ));
+                }
+            }
+        }
+        lines.push(("font", format!("{} {}", "Font:".bold().yellow(), self.font)));
+        let overrides: Vec<String> = self
+            .locale
+            .overrides
+            .iter()
+            .map(|(category, value)| format!("{category}={value}"))
+            .collect();
+        let locale = self.templated(
+            "locale",
+            &[
+                (
+                    "lang",
+                    Some(self.locale.lang.clone().unwrap_or_else(|| "C".to_string())),
+                ),
+                (
+                    "overrides",
+                    Some(overrides.join(", ")).filter(|o| !o.is_empty()),
+                ),
+            ],
+            || self.locale.format(),
+        );
+        lines.push((
+            "locale",
+            format!("{} {}", "Locale:".bold().yellow(), locale),
+        ));
+        if let Some(timezone) = &self.timezone {
+            let offset = self.datetime.format("%:z").to_string();
+            let timezone = self.templated(
+                "timezone",
+                &[
+                    ("name", Some(timezone.clone())),
+                    ("offset", Some(offset.clone())),
+                ],
+                || format!("{timezone} (UTC{offset})"),
+            );
+            lines.push((
+                "timezone",
+                format!("{} {}", "Timezone:".bold().yellow(), timezone),
+            ));
+        }
+        if let Some(keyboard) = self.keyboard.format() {
+            let keyboard = self.templated(
+                "keyboard",
+                &[
+                    ("layout", self.keyboard.layout.clone()),
+                    ("variant", self.keyboard.variant.clone()),
+                    ("keymap", self.keyboard.keymap.clone()),
+                ],
+                || keyboard,
+            );
+            lines.push((
+                "keyboard",
+                format!("{} {}", "Keyboard:".bold().yellow(), keyboard),
+            ));
+        }
+        let cpu = self.templated(
+            "cpu",
+            &[
+                ("brand", Some(self.cpu.brand.clone())),
+                ("cores", Some(self.cpu.cores.to_string())),
+                ("threads", Some(self.cpu.threads.to_string())),
+                (
+                    "frequency",
+                    self.cpu
+                        .current_mhz
+                        .or(self.cpu.max_mhz)
+                        .map(|mhz| format!("{:.2} GHz", mhz as f64 / 1000.0)),
+                ),
+                (
+                    "temperature",
+                    self.cpu
+                        .temperature
+                        .map(|temperature| format!("{temperature:.1}°C")),
+                ),
+            ],
+            || self.cpu.format(&self.module.cpu),
+        );
+        lines.push(("cpu", format!("{} {}", "CPU:".bold().yellow(), cpu)));
+        if let Some(cpu_usage) = self.cpu_usage {
+            lines.push((
+                "cpu_usage",
+                format!(
+                    "{} {}",
+                    "CPU Usage:".bold().yellow(),
+                    self.usage.format(cpu_usage, &format!("{cpu_usage}%"))
+                ),
+            ));
+        }
+        lines.push(("gpu", format!("{} {}", "GPU:".bold().yellow(), self.gpu)));
+        let memory_percent = (self.memory_used * 100)
+            .checked_div(self.memory_total)
+            .unwrap_or(0);
+        let memory = self.usage_templated(
+            "memory",
+            memory_percent,
+            self.memory_used / 1024 / 1024,
+            self.memory_total / 1024 / 1024,
+            "MB",
+            &[],
+        );
+        lines.push((
+            "memory",
+            format!("{} {}", "Memory:".bold().yellow(), memory),
+        ));
+        let zram = self.swap.zram.then(|| "zram".to_string());
+        let swap = self.usage_templated(
+            "swap",
+            self.swap.percent(),
+            self.swap.used / 1024 / 1024,
+            self.swap.total / 1024 / 1024,
+            "MB",
+            &[("zram", zram)],
+        );
+        lines.push(("swap", format!("{} {}", "Swap:".bold().yellow(), swap)));
+        let normalize = self.module.load.normalize.unwrap_or(true);
+        let [one, five, fifteen] = self
+            .load
+            .averages(normalize)
+            .map(|average| Some(format!("{average:.2}")));
+        let load = self.templated(
+            "load",
+            &[
+                ("one", one),
+                ("five", five),
+                ("fifteen", fifteen),
+                ("percent", Some(self.load.percent().to_string())),
+            ],
+            || self.load.format(normalize),
+        );
+        lines.push((
+            "load",
+            format!(
+                "{} {}",
+                "Load:".bold().yellow(),
+                self.usage.format(self.load.percent(), &load)
+            ),
+        ));
+        let processes = self.templated(
+            "processes",
+            &[
+                ("processes", Some(self.processes.processes.to_string())),
+                ("threads", Some(self.processes.threads.to_string())),
+            ],
+            || {
+                format!(
+                    "{} ({} threads)",
+                    self.processes.processes, self.processes.threads
+                )
+            },
+        );
+        lines.push((
+            "processes",
+            format!("{} {}", "Processes:".bold().yellow(), processes),
+        ));
+        for battery in &self.power.batteries {
+            let value = self.templated(
+                "battery",
+                &[
+                    ("name", Some(battery.name.clone())),
+                    ("capacity", Some(battery.capacity.to_string())),
+                    ("status", Some(battery.status.clone())),
+                    (
+                        "time",
+                        battery
+                            .minutes_left
+                            .map(|minutes| format!("{}h {}m", minutes / 60, minutes % 60)),
+                    ),
+                    ("health", battery.health.map(|health| health.to_string())),
+                ],
+                || battery.format(&self.module.battery),
+            );
+            lines.push((
+                "battery",
+                format!(
+                    "{} {}",
+                    format!("Battery ({}):", battery.name).bold().yellow(),
+                    // a nearly empty battery is the critical case
+                    self.usage.format_level(battery.capacity, &value)
+                ),
+            ));
+        }
+        if !self.power.batteries.is_empty()
+            && let Some(ac_online) = self.power.ac_online
+        {
+            let source = if ac_online { "AC adapter" } else { "Battery" };
+            lines.push((
+                "battery",
+                format!("{} {}", "Power:".bold().yellow(), source),
+            ));
+        }
+        for interface in &self.network {
+            let (ssid, signal) = match &interface.kind {
+                LinkKind::Wireless { ssid, signal } => {
+                    (ssid.clone(), signal.map(|signal| signal.to_string()))
+                }
+                _ => (None, None),
+            };
+            let addresses = interface.visible_addresses(&self.module.network).join(", ");
+            let (download, upload) = interface
+                .throughput
+                .map(|(rx, tx)| (network::format_rate(rx), network::format_rate(tx)))
+                .unzip();
+            let value = self.templated(
+                "network",
+                &[
+                    ("name", Some(interface.name.clone())),
+                    ("addresses", Some(addresses).filter(|a| !a.is_empty())),
+                    ("speed", interface.speed.map(|speed| speed.to_string())),
+                    ("ssid", ssid),
+                    ("signal", signal),
+                    ("download", download),
+                    ("upload", upload),
+                ],
+                || interface.format(&self.module.network),
+            );
+            lines.push((
+                "network",
+                format!(
+                    "{} {}",
+                    format!("Network ({}):", interface.name).bold().yellow(),
+                    value
+                ),
+            ));
+        }
+        let storage_percent = (self.storage_used * 100)
+            .checked_div(self.storage_total)
+            .unwrap_or(0);
+        let storage = self.usage_templated(
+            "storage",
+            storage_percent,
+            self.storage_used / 1024 / 1024 / 1024,
+            self.storage_total / 1024 / 1024 / 1024,
+            "GB",
+            &[],
+        );
+        lines.push((
+            "storage",
+            format!("{} {}", "Storage:".bold().yellow(), storage),
+        ));
+        for disk in &self.disks {
+            let (used, total) = (
+                disk.used() / 1024 / 1024 / 1024,
+                disk.total / 1024 / 1024 / 1024,
+            );
+            let value = self.templated(
+                "disk",
+                &[
+                    ("mount", Some(disk.mount_point.display().to_string())),
+                    ("device", Some(disk.device.clone())),
+                    ("used", Some(format!("{used}GB"))),
+                    ("total", Some(format!("{total}GB"))),
+                    ("percent", Some(disk.percent().to_string())),
+                    ("fs", Some(disk.fs_type.clone())),
+                ],
+                || {
+                    format!(
+                        "{used}GB / {total}GB ({}%) - {}",
+                        disk.percent(),
+                        disk.fs_type
+                    )
+                },
+            );
+            lines.push((
+                "disk",
+                format!(
+                    "{} {}",
+                    format!("Disk ({}):", disk.mount_point.display())
+                        .bold()
+                        .yellow(),
+                    self.usage.format(disk.percent(), &value)
+                ),
+            ));
+        }
+        for line in &self.custom {
+            let value = match &line.value {
+                Ok(value) => value.clone(),
+                Err(err) => format!("({err})").dimmed().to_string(),
+            };
+            lines.push((
+                "custom",
+                format!("{} {}", format!("{}:", line.label).bold().yellow(), value),
+            ));
+        }
+        let colors = &self.module.colors;
+        if colors.enabled.unwrap_or(false) && control::SHOULD_COLORIZE.should_colorize() {
+            lines.push(("colors", String::new()));
+            lines.extend(
+                colors::lines(colors)
+                    .into_iter()
+                    .map(|line| ("colors", line)),
+            );
+        }
         lines
     }
+
+    /// Renders the `format` string of `module` with `values`, or `default` if it has none.
+    fn templated(
+        &self,
+        module: &str,
+        values: &[(&str, Option<String>)],
+        default: impl FnOnce() -> String,
+    ) -> String {
+        debug_assert!(
+            FORMAT_FIELDS
+                .iter()
+                .find(|(name, _)| *name == module)
+                .is_some_and(|(_, fields)| values.iter().all(|(field, _)| fields.contains(field))),
+            "values of `{module}` don't match FORMAT_FIELDS"
+        );
+        // broken templates are reported when loading the config, fall back to the default here
+        match self
+            .module
+            .format(module)
+            .and_then(|format| Template::parse(format).ok())
+        {
+            Some(template) => template.render(values),
+            None => default(),
+        }
+    }
+
+    /// [`Self::templated`] for `used / total` lines, decorated with a usage bar.
+    fn usage_templated(
+        &self,
+        module: &str,
+        percent: u64,
+        used: u64,
+        total: u64,
+        unit: &str,
+        extra: &[(&str, Option<String>)],
+    ) -> String {
+        let mut values = vec![
+            ("used", Some(format!("{used}{unit}"))),
+            ("total", Some(format!("{total}{unit}"))),
+            ("percent", Some(percent.to_string())),
+        ];
+        values.extend_from_slice(extra);
+        let value = self.templated(module, &values, || {
+            let suffix = extra
+                .iter()
+                .filter_map(|(_, value)| value.as_ref())
+                .map(|value| format!(" ({value})"))
+                .collect::<String>();
+            format!("{used}{unit} / {total}{unit}{suffix}")
+        });
+        self.usage.format(percent, &value)
+    }
+}
+
+/// Returns the disks to list on their own lines and the used and total bytes of all of them.
+fn storage(config: &DiskConfig) -> (Vec<DiskEntry>, u64, u64) {
+    let disks = disk::filter(disk::probe(), config);
+    let total: u64 = disks.iter().map(|disk| disk.total).sum();
+    let used: u64 = disks.iter().map(|disk| disk.used()).sum();
+    let disks = if config.show_mounts.unwrap_or(false) {
+        disks
+    } else {
+        Vec::new()
+    };
+    (disks, used, total)
 }
 
 impl Default for SystemInfo {
     fn default() -> Self {
-        Self::new()
+        Self::new(&Config::default())
     }
 }
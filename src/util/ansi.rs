@@ -0,0 +1,275 @@
+//! helpers for strings containing ANSI escape sequences
+use colored::Color;
+
+/// Returns the number of characters that are actually displayed, ignoring escape sequences.
+///
+/// ```
+/// use symfetch::util::ansi::visible_width;
+///
+/// assert_eq!(visible_width("\x1b[1;33mCPU:\x1b[0m 8 cores"), 12);
+/// ```
+pub fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            skip_escape(&mut chars);
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+/// Pads `line` with spaces up to `width` visible characters.
+///
+/// ```
+/// use symfetch::util::ansi::pad;
+///
+/// assert_eq!(pad("\x1b[31mab\x1b[0m", 4), "\x1b[31mab\x1b[0m  ");
+/// ```
+pub fn pad(line: &str, width: usize) -> String {
+    let padding = width.saturating_sub(visible_width(line));
+    format!("{line}{}", " ".repeat(padding))
+}
+
+/// consumes the rest of an escape sequence whose `ESC` was already read
+pub(crate) fn skip_escape(chars: &mut std::str::Chars) {
+    match chars.next() {
+        // CSI: parameters and intermediates up to a final byte in @..~
+        Some('[') => {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+        // OSC: terminated by BEL or ST (ESC \)
+        Some(']') => {
+            while let Some(c) = chars.next() {
+                if c == '\x07' {
+                    break;
+                }
+                if c == '\x1b' {
+                    chars.next();
+                    break;
+                }
+            }
+        }
+        // character set selection such as `ESC (B`
+        Some('(' | ')') => {
+            chars.next();
+        }
+        Some(_) | None => {}
+    }
+}
+
+/// Cuts `line` down to `width` visible characters, ending it with `…` if anything was cut.
+///
+/// Escape sequences are kept, so colors that were switched on are reset at the end.
+///
+/// ```
+/// use symfetch::util::ansi::truncate;
+///
+/// assert_eq!(truncate("Shell: fish 3.7.1", 10), "Shell: fi…");
+/// assert_eq!(truncate("\x1b[33mCPU:\x1b[0m Ryzen", 6), "\x1b[33mCPU:\x1b[0m …\x1b[0m");
+/// assert_eq!(truncate("short", 10), "short");
+/// ```
+pub fn truncate(line: &str, width: usize) -> String {
+    if visible_width(line) <= width {
+        return line.to_string();
+    }
+
+    let mut out = String::new();
+    let mut escaped = false;
+    let mut visible = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // copy the whole sequence
+            let rest = chars.as_str();
+            skip_escape(&mut chars);
+            let consumed = rest.len() - chars.as_str().len();
+            out.push('\x1b');
+            out.push_str(&rest[..consumed]);
+            escaped = true;
+        } else if visible + 1 < width {
+            out.push(c);
+            visible += 1;
+        } else {
+            break;
+        }
+    }
+    if width > 0 {
+        out.push('…');
+    }
+    if escaped {
+        out.push_str("\x1b[0m");
+    }
+    out
+}
+
+/// an RGB color
+pub type Rgb = (u8, u8, u8);
+
+/// text attributes set through SGR escape sequences
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub fg: Option<Rgb>,
+    pub bg: Option<Rgb>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+/// a run of text sharing one style
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+/// Splits `line` into styled runs, interpreting SGR sequences and dropping all other escapes.
+///
+/// ```
+/// use symfetch::util::ansi::spans;
+///
+/// let spans = spans("\x1b[1;33mCPU:\x1b[0m Ryzen");
+/// assert_eq!(spans[0].text, "CPU:");
+/// assert_eq!(spans[0].style.fg, Some((205, 205, 0)));
+/// assert!(spans[0].style.bold);
+/// assert_eq!(spans[1].text, " Ryzen");
+/// assert_eq!(spans[1].style.fg, None);
+/// ```
+pub fn spans(line: &str) -> Vec<Span> {
+    let mut spans: Vec<Span> = Vec::new();
+    let mut style = Style::default();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            match spans.last_mut() {
+                Some(span) if span.style == style => span.text.push(c),
+                _ => spans.push(Span {
+                    text: c.to_string(),
+                    style,
+                }),
+            }
+            continue;
+        }
+
+        let rest = chars.as_str();
+        skip_escape(&mut chars);
+        let sequence = &rest[..rest.len() - chars.as_str().len()];
+        if let Some(params) = sequence
+            .strip_prefix('[')
+            .and_then(|sequence| sequence.strip_suffix('m'))
+        {
+            apply_sgr(&mut style, params);
+        }
+    }
+    spans
+}
+
+fn apply_sgr(style: &mut Style, params: &str) {
+    let params: Vec<u16> = params
+        .split(';')
+        .map(|param| param.parse().unwrap_or(0))
+        .collect();
+    let mut params = params.into_iter();
+    while let Some(param) = params.next() {
+        match param {
+            0 => *style = Style::default(),
+            1 => style.bold = true,
+            2 => style.dim = true,
+            3 => style.italic = true,
+            4 => style.underline = true,
+            22 => (style.bold, style.dim) = (false, false),
+            23 => style.italic = false,
+            24 => style.underline = false,
+            30..=37 => style.fg = Some(palette(param as u8 - 30)),
+            90..=97 => style.fg = Some(palette(param as u8 - 90 + 8)),
+            40..=47 => style.bg = Some(palette(param as u8 - 40)),
+            100..=107 => style.bg = Some(palette(param as u8 - 100 + 8)),
+            39 => style.fg = None,
+            49 => style.bg = None,
+            38 | 48 => {
+                let color = match params.next() {
+                    Some(5) => params.next().map(|index| palette(index as u8)),
+                    Some(2) => match (params.next(), params.next(), params.next()) {
+                        (Some(r), Some(g), Some(b)) => Some((r as u8, g as u8, b as u8)),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                if param == 38 {
+                    style.fg = color;
+                } else {
+                    style.bg = color;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the color of a 256 color palette index, using the xterm defaults for 0-15.
+///
+/// ```
+/// use symfetch::util::ansi::palette;
+///
+/// assert_eq!(palette(1), (205, 0, 0));
+/// assert_eq!(palette(196), (255, 0, 0));
+/// assert_eq!(palette(244), (128, 128, 128));
+/// ```
+pub fn palette(index: u8) -> Rgb {
+    const BASIC: [Rgb; 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    match index {
+        0..=15 => BASIC[index as usize],
+        16..=231 => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+            (level(index / 36), level((index / 6) % 6), level(index % 6))
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// Returns the RGB value of a [`Color`], named colors use [`palette`].
+///
+/// ```
+/// use colored::Color;
+/// use symfetch::util::ansi::rgb;
+///
+/// assert_eq!(rgb(Color::Yellow), (205, 205, 0));
+/// assert_eq!(rgb("#1e1e1e".parse().unwrap()), (30, 30, 30));
+/// ```
+pub fn rgb(color: Color) -> Rgb {
+    // `to_fg_str` approximates true colors when the terminal doesn't support them
+    if let Color::TrueColor { r, g, b } = color {
+        return (r, g, b);
+    }
+    let mut style = Style::default();
+    apply_sgr(&mut style, &color.to_fg_str());
+    style.fg.unwrap_or_default()
+}
@@ -0,0 +1,22 @@
+use std::path::Path;
+
+/// Reads a small text file such as a sysfs attribute and trims surrounding whitespace.
+///
+/// # Arguments
+/// * `path` - file to read
+///
+/// # Returns
+/// * `Option<String>` - the trimmed content or `None` if the file can't be read
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::util::fs_utils::read_trimmed;
+///
+/// let online = read_trimmed(Path::new("tests/fixtures/root/sys/devices/system/cpu/online"));
+/// assert_eq!(online.as_deref(), Some("0-5"));
+/// ```
+pub fn read_trimmed(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|content| content.trim().to_string())
+}
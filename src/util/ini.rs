@@ -0,0 +1,42 @@
+//! minimal reader for INI style configuration files
+//!
+//! Covers what desktop settings files (`settings.ini`, `kdeglobals`, `index.theme`)
+//! need: `[Section]` headers and `key=value` pairs.
+
+/// Looks up `key` inside `[section]`.
+///
+/// # Arguments
+/// * `content` - the whole file
+/// * `section` - section name without brackets
+/// * `key` - key to look up
+///
+/// # Returns
+/// * `Option<String>` - the trimmed value, `None` if missing or empty
+///
+/// ```
+/// use symfetch::util::ini::get;
+///
+/// let ini = "[Settings]\ngtk-theme-name = Adwaita-dark\n[Other]\ngtk-theme-name=Nope\n";
+/// assert_eq!(get(ini, "Settings", "gtk-theme-name").as_deref(), Some("Adwaita-dark"));
+/// assert_eq!(get(ini, "Settings", "gtk-font-name"), None);
+/// ```
+pub fn get(content: &str, section: &str, key: &str) -> Option<String> {
+    let mut current = None;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = Some(name);
+            continue;
+        }
+        if current == Some(section)
+            && let Some((k, value)) = line.split_once('=')
+            && k.trim() == key
+        {
+            let value = value.trim().trim_matches('"');
+            return (!value.is_empty()).then(|| value.to_string());
+        }
+    }
+    None
+}
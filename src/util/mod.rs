@@ -1,2 +1,10 @@
 //! Collection of Utility Class and Functions
+pub mod ansi;
+pub mod fs_utils;
+pub mod ini;
 pub mod path_utils;
+pub mod process;
+pub mod sqlite;
+pub mod template;
+pub mod term;
+pub mod usage;
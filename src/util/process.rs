@@ -0,0 +1,157 @@
+//! walk the process tree through `/proc` and run commands with a deadline
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::util::fs_utils::read_trimmed;
+
+/// a process as seen in `/proc/<pid>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    /// short name from `/proc/<pid>/comm`, truncated to 15 bytes by the kernel
+    pub name: String,
+    /// resolved `/proc/<pid>/exe`, not readable for processes of other users
+    pub exe: Option<PathBuf>,
+}
+
+impl ProcessInfo {
+    /// Reads a single process.
+    ///
+    /// # Arguments
+    /// * `root` - filesystem root containing `proc/`, `/` on a live system
+    /// * `pid` - process to read
+    pub fn read(root: &Path, pid: u32) -> Option<Self> {
+        let dir = root.join("proc").join(pid.to_string());
+        let stat = fs::read_to_string(dir.join("stat")).ok()?;
+        // the name in parentheses may itself contain spaces and parentheses
+        let (name, fields) = stat.rsplit_once(')')?;
+        let ppid = fields.split_whitespace().nth(1)?.parse().ok()?;
+        let name = read_trimmed(&dir.join("comm"))
+            .or_else(|| Some(name.split_once('(')?.1.to_string()))?;
+
+        Some(ProcessInfo {
+            pid,
+            ppid,
+            name,
+            exe: fs::read_link(dir.join("exe")).ok(),
+        })
+    }
+
+    /// File name of the executable, falling back to the process name.
+    pub fn exe_name(&self) -> String {
+        self.exe
+            .as_ref()
+            .and_then(|exe| exe.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.name.clone())
+    }
+}
+
+/// Returns the parent, grandparent, ... of `pid`, closest first, stopping before `init`.
+///
+/// ```
+/// use std::path::Path;
+/// use symfetch::util::process::ancestors;
+///
+/// let names: Vec<String> = ancestors(Path::new("tests/fixtures/root"), 300)
+///     .into_iter()
+///     .map(|process| process.name)
+///     .collect();
+/// assert_eq!(names, vec!["fish", "bash", "kitty"]);
+/// ```
+pub fn ancestors(root: &Path, pid: u32) -> Vec<ProcessInfo> {
+    let mut ancestors = Vec::new();
+    let mut current = ProcessInfo::read(root, pid);
+
+    while let Some(process) = current {
+        if process.ppid <= 1 || ancestors.len() > 64 {
+            break;
+        }
+        current = ProcessInfo::read(root, process.ppid);
+        if let Some(parent) = &current {
+            ancestors.push(parent.clone());
+        }
+    }
+    ancestors
+}
+
+/// Returns every process below `proc/`.
+///
+/// # Arguments
+/// * `root` - filesystem root containing `proc/`, `/` on a live system
+pub fn list(root: &Path) -> Vec<ProcessInfo> {
+    fs::read_dir(root.join("proc"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter_map(|pid| ProcessInfo::read(root, pid))
+        .collect()
+}
+
+/// Runs `command` and returns its stdout, killing it after `timeout`.
+///
+/// The deadline also covers processes the command left running in the background
+/// that still hold its stdout open.
+pub fn output_with_timeout(command: &mut Command, timeout: Duration) -> Result<String, String> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    // a process group of its own lets a timeout kill everything the command started
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(command, 0);
+    let mut child = command.spawn().map_err(|err| err.to_string())?;
+
+    // read concurrently so a chatty command can't block on a full pipe
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stdout.read_to_end(&mut output);
+        let _ = sender.send(output);
+    });
+
+    let start = Instant::now();
+    let timed_out = |child: &mut Child| {
+        kill(child);
+        Err(format!("timed out after {}s", timeout.as_secs()))
+    };
+    let status = loop {
+        match child.try_wait().map_err(|err| err.to_string())? {
+            Some(status) => break status,
+            None if start.elapsed() >= timeout => return timed_out(&mut child),
+            None => thread::sleep(Duration::from_millis(10)),
+        }
+    };
+
+    let output = match receiver.recv_timeout(timeout.saturating_sub(start.elapsed())) {
+        Ok(output) => output,
+        Err(mpsc::RecvTimeoutError::Timeout) => return timed_out(&mut child),
+        Err(mpsc::RecvTimeoutError::Disconnected) => Vec::new(),
+    };
+    if !status.success() {
+        return Err(match status.code() {
+            Some(code) => format!("exited with status {code}"),
+            None => "killed by a signal".to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
+
+/// Kills `child` together with everything else in its process group.
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    // SAFETY: kill(2) with a negative pid only sends a signal to that process group
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
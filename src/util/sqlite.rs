@@ -0,0 +1,241 @@
+//! just enough of the SQLite file format to count the rows of a table
+//!
+//! See <https://www.sqlite.org/fileformat.html>. Only the pages on the way are read, the
+//! database may be hundreds of megabytes. Anything unexpected, e.g. a database truncated
+//! in the middle of a transaction, makes the count unknown rather than panicking.
+use std::collections::HashSet;
+use std::io::{Read, Seek, SeekFrom};
+
+/// b-trees deeper than this are treated as corrupt, real ones stay far below it
+const MAX_DEPTH: usize = 32;
+
+/// Counts the rows of `table` by walking its b-tree.
+///
+/// Overflow pages are never followed: rows are only counted, and the columns looked up
+/// in the schema come first in a record, so they always fit on the b-tree page.
+///
+/// # Arguments
+/// * `file` - the database file
+/// * `table` - name of the table as given in `CREATE TABLE`
+///
+/// # Returns
+/// * `Option<usize>` - the number of rows, `None` if the table doesn't exist or the
+///   database is corrupt
+///
+/// ```
+/// use std::fs::File;
+/// use symfetch::util::sqlite::count_rows;
+///
+/// let db = File::open("tests/fixtures/root/var/lib/rpm/rpmdb.sqlite").unwrap();
+/// assert_eq!(count_rows(db, "Packages"), Some(3));
+///
+/// let db = File::open("tests/fixtures/root/var/lib/rpm/rpmdb.sqlite").unwrap();
+/// assert_eq!(count_rows(db, "Missing"), None);
+/// ```
+pub fn count_rows(file: impl Read + Seek, table: &str) -> Option<usize> {
+    let mut db = Db::open(file)?;
+
+    // the schema table lives in the b-tree rooted at page 1
+    let mut root_page = None;
+    db.walk(1, 0, &mut HashSet::new(), &mut |payload| {
+        let columns = record(payload);
+        if columns.first() == Some(&Column::Text(b"table"))
+            && columns.get(1) == Some(&Column::Text(table.as_bytes()))
+            && let Some(Column::Int(page)) = columns.get(3)
+        {
+            root_page = usize::try_from(*page).ok();
+        }
+    })?;
+
+    let mut rows = 0;
+    db.walk(root_page?, 0, &mut HashSet::new(), &mut |_| rows += 1)?;
+    Some(rows)
+}
+
+#[derive(Debug, PartialEq)]
+enum Column<'a> {
+    Int(i64),
+    Text(&'a [u8]),
+    Other,
+}
+
+struct Db<R> {
+    file: R,
+    page_size: usize,
+    /// bytes of each page not reserved for extensions
+    usable_size: usize,
+    /// number of complete pages in the file
+    pages: usize,
+}
+
+impl<R: Read + Seek> Db<R> {
+    fn open(mut file: R) -> Option<Self> {
+        let mut header = [0; 100];
+        file.read_exact(&mut header).ok()?;
+        if !header.starts_with(b"SQLite format 3\0") {
+            return None;
+        }
+        let page_size = match u16::from_be_bytes([header[16], header[17]]) {
+            1 => 65536,
+            size => size as usize,
+        };
+        if page_size < 512 || !page_size.is_power_of_two() {
+            return None;
+        }
+        // the usable size must leave room for at least 480 bytes of cells
+        let usable_size = page_size - header[20] as usize;
+        if usable_size < 480 {
+            return None;
+        }
+        let len = file.seek(SeekFrom::End(0)).ok()?;
+        let pages = usize::try_from(len / page_size as u64).ok()?;
+        Some(Db {
+            file,
+            page_size,
+            usable_size,
+            pages,
+        })
+    }
+
+    /// reads page number `page`, counting from 1
+    fn page(&mut self, page: usize) -> Option<Vec<u8>> {
+        if page == 0 || page > self.pages {
+            return None;
+        }
+        let mut data = vec![0; self.page_size];
+        self.file
+            .seek(SeekFrom::Start(((page - 1) * self.page_size) as u64))
+            .ok()?;
+        self.file.read_exact(&mut data).ok()?;
+        Some(data)
+    }
+
+    /// Returns how much of a payload of `size` bytes is stored on a leaf table page,
+    /// see "Cell Payload Overflow Pages".
+    fn local_size(&self, size: usize) -> usize {
+        let usable = self.usable_size;
+        let max_local = usable - 35;
+        if size <= max_local {
+            return size;
+        }
+        let min_local = (usable - 12) * 32 / 255 - 23;
+        let local = min_local + (size - min_local) % (usable - 4);
+        if local <= max_local { local } else { min_local }
+    }
+
+    /// calls `visit` with the local payload of every row of a table b-tree
+    fn walk(
+        &mut self,
+        page: usize,
+        depth: usize,
+        seen: &mut HashSet<usize>,
+        visit: &mut dyn FnMut(&[u8]),
+    ) -> Option<()> {
+        // a page referenced twice means the tree is corrupt and may loop
+        if depth > MAX_DEPTH || !seen.insert(page) {
+            return None;
+        }
+        let data = self.page(page)?;
+        // page 1 starts with the 100 byte database header
+        let header = if page == 1 { 100 } else { 0 };
+        let be16 = |at: usize| {
+            let bytes = data.get(at..at + 2)?;
+            Some(u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+        };
+        let be32 = |at: usize| {
+            let bytes = data.get(at..at + 4)?;
+            Some(u32::from_be_bytes(bytes.try_into().ok()?) as usize)
+        };
+
+        let kind = *data.get(header)?;
+        let cells = be16(header + 3)?;
+        let header_size = if kind == 0x05 { 12 } else { 8 };
+
+        for cell in 0..cells {
+            let offset = be16(header + header_size + cell * 2)?;
+            match kind {
+                // interior table page: left child pointer followed by the key
+                0x05 => {
+                    let child = be32(offset)?;
+                    self.walk(child, depth + 1, seen, visit)?;
+                }
+                // leaf table page: payload size, rowid, payload, overflow page number
+                0x0d => {
+                    let (size, read) = varint(data.get(offset..)?);
+                    let (_, rowid_len) = varint(data.get(offset + read..)?);
+                    let start = offset + read + rowid_len;
+                    let end = start.checked_add(self.local_size(usize::try_from(size).ok()?))?;
+                    visit(data.get(start..end)?);
+                }
+                _ => return None,
+            }
+        }
+
+        if kind == 0x05 {
+            let right = be32(header + 8)?;
+            self.walk(right, depth + 1, seen, visit)?;
+        }
+        Some(())
+    }
+}
+
+/// decodes the columns of a record, see "Record Format"
+///
+/// Columns that continue on an overflow page are left out.
+fn record(payload: &[u8]) -> Vec<Column<'_>> {
+    let (header_size, mut pos) = varint(payload);
+    let mut body = header_size as usize;
+    let mut columns = Vec::new();
+
+    while pos < header_size as usize {
+        let Some(rest) = payload.get(pos..).filter(|rest| !rest.is_empty()) else {
+            break;
+        };
+        let (serial, read) = varint(rest);
+        pos += read;
+        let size = match serial {
+            0 | 8 | 9 => 0,
+            1..=4 => serial as usize,
+            5 => 6,
+            6 | 7 => 8,
+            n if n >= 12 => ((n - 12) / 2) as usize,
+            _ => 0,
+        };
+        let Some(bytes) = body
+            .checked_add(size)
+            .and_then(|end| payload.get(body..end))
+        else {
+            break;
+        };
+        columns.push(match serial {
+            1..=6 => Column::Int(
+                bytes
+                    .iter()
+                    .fold(if bytes[0] & 0x80 != 0 { -1 } else { 0 }, |acc, byte| {
+                        (acc << 8) | *byte as i64
+                    }),
+            ),
+            8 => Column::Int(0),
+            9 => Column::Int(1),
+            n if n >= 13 && n % 2 == 1 => Column::Text(bytes),
+            _ => Column::Other,
+        });
+        body += size;
+    }
+    columns
+}
+
+/// decodes a big-endian variable-length integer, returning it and its length
+fn varint(bytes: &[u8]) -> (u64, usize) {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().enumerate().take(9) {
+        if i == 8 {
+            return ((value << 8) | *byte as u64, 9);
+        }
+        value = (value << 7) | (*byte & 0x7f) as u64;
+        if byte & 0x80 == 0 {
+            return (value, i + 1);
+        }
+    }
+    (value, bytes.len())
+}
@@ -0,0 +1,220 @@
+//! format strings with named placeholders for info lines
+use std::fmt;
+
+use serde_derive::Deserialize;
+
+/// store parsed information from a module table that only supports a `format` string,
+/// e.g. "\[module.memory\]"
+///
+/// ```toml
+/// [module.memory]
+/// # Optional: format = "{used} / {total} ({percent}%)"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct FormatConfig {
+    /// template for the value of the line, see [`Template`]
+    pub format: Option<String>,
+}
+
+/// why a format string was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    /// byte offset into the format string
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (at position {})", self.message, self.position)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    /// `{name}`, position is kept for error messages
+    Field(String, usize),
+    /// `{?name:body}`, body is only rendered when `name` has a value
+    Conditional(String, usize, Vec<Segment>),
+}
+
+/// A parsed format string.
+///
+/// * `{name}` is replaced by the value of `name`, or nothing if it has none
+/// * `{?name:text}` renders `text` only if `name` has a value, `text` may contain placeholders
+/// * `{{` and `}}` are literal braces
+///
+/// ```
+/// use symfetch::util::template::Template;
+///
+/// let template = Template::parse("{name}{?version: v{version}}").unwrap();
+/// assert_eq!(template.render(&[("name", Some("fish".to_string())), ("version", Some("3.7.1".to_string()))]), "fish v3.7.1");
+/// assert_eq!(template.render(&[("name", Some("dash".to_string())), ("version", None)]), "dash");
+///
+/// let error = Template::parse("{used} / {total").unwrap_err();
+/// assert_eq!(error.to_string(), "unclosed `{` (at position 9)");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// Parses `format`, reporting the first syntax error.
+    pub fn parse(format: &str) -> Result<Self, TemplateError> {
+        let mut parser = Parser {
+            chars: format.char_indices().peekable(),
+            len: format.len(),
+        };
+        let segments = parser.segments(false)?;
+        Ok(Template { segments })
+    }
+
+    /// Checks that every placeholder is one of `fields`.
+    ///
+    /// ```
+    /// use symfetch::util::template::Template;
+    ///
+    /// let template = Template::parse("{used} / {totl}").unwrap();
+    /// assert_eq!(
+    ///     template.validate(&["used", "total", "percent"]).unwrap_err().to_string(),
+    ///     "unknown placeholder `{totl}`, expected one of used, total, percent (at position 9)"
+    /// );
+    /// ```
+    pub fn validate(&self, fields: &[&str]) -> Result<(), TemplateError> {
+        fn check(segments: &[Segment], fields: &[&str]) -> Result<(), TemplateError> {
+            for segment in segments {
+                let (name, position) = match segment {
+                    Segment::Text(_) => continue,
+                    Segment::Field(name, position) => (name, *position),
+                    Segment::Conditional(name, position, body) => {
+                        check(body, fields)?;
+                        (name, *position)
+                    }
+                };
+                if !fields.contains(&name.as_str()) {
+                    return Err(TemplateError {
+                        position,
+                        message: format!(
+                            "unknown placeholder `{{{name}}}`, expected one of {}",
+                            fields.join(", ")
+                        ),
+                    });
+                }
+            }
+            Ok(())
+        }
+        check(&self.segments, fields)
+    }
+
+    /// Renders the template, placeholders missing from `values` render as nothing.
+    pub fn render(&self, values: &[(&str, Option<String>)]) -> String {
+        fn render(segments: &[Segment], values: &[(&str, Option<String>)], out: &mut String) {
+            let value = |name: &str| {
+                values
+                    .iter()
+                    .find(|(field, _)| *field == name)
+                    .and_then(|(_, value)| value.as_deref())
+                    .filter(|value| !value.is_empty())
+            };
+            for segment in segments {
+                match segment {
+                    Segment::Text(text) => out.push_str(text),
+                    Segment::Field(name, _) => out.push_str(value(name).unwrap_or_default()),
+                    Segment::Conditional(name, _, body) => {
+                        if value(name).is_some() {
+                            render(body, values, out);
+                        }
+                    }
+                }
+            }
+        }
+        let mut out = String::new();
+        render(&self.segments, values, &mut out);
+        out
+    }
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    len: usize,
+}
+
+impl Parser<'_> {
+    /// parses until the end of input, or the `}` closing a conditional if `nested`
+    fn segments(&mut self, nested: bool) -> Result<Vec<Segment>, TemplateError> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        while let Some((position, c)) = self.chars.next() {
+            match c {
+                '{' if self.chars.next_if(|(_, c)| *c == '{').is_some() => text.push('{'),
+                '}' if self.chars.next_if(|(_, c)| *c == '}').is_some() => text.push('}'),
+                '}' if nested => {
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(text));
+                    }
+                    return Ok(segments);
+                }
+                '}' => {
+                    return Err(TemplateError {
+                        position,
+                        message: "unmatched `}`, use `}}` for a literal brace".to_string(),
+                    });
+                }
+                '{' => {
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(self.placeholder(position)?);
+                }
+                c => text.push(c),
+            }
+        }
+        if nested {
+            return Err(TemplateError {
+                position: self.len,
+                message: "unclosed conditional, expected `}`".to_string(),
+            });
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        Ok(segments)
+    }
+
+    /// parses what follows a `{` at `start`
+    fn placeholder(&mut self, start: usize) -> Result<Segment, TemplateError> {
+        let conditional = self.chars.next_if(|(_, c)| *c == '?').is_some();
+        let mut name = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, c)) if c.is_ascii_alphanumeric() || c == '_' => name.push(c),
+                Some((position, c)) if name.is_empty() => {
+                    return Err(TemplateError {
+                        position,
+                        message: format!("expected a placeholder name, found `{c}`"),
+                    });
+                }
+                Some((_, '}')) if !conditional => return Ok(Segment::Field(name, start)),
+                Some((_, ':')) if conditional => {
+                    let body = self.segments(true)?;
+                    return Ok(Segment::Conditional(name, start, body));
+                }
+                Some((position, c)) => {
+                    let expected = if conditional { ":" } else { "}" };
+                    return Err(TemplateError {
+                        position,
+                        message: format!("expected `{expected}` after `{name}`, found `{c}`"),
+                    });
+                }
+                None => {
+                    return Err(TemplateError {
+                        position: start,
+                        message: "unclosed `{`".to_string(),
+                    });
+                }
+            }
+        }
+    }
+}
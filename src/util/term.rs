@@ -0,0 +1,32 @@
+//! queries about the terminal symfetch prints to
+
+/// Returns the width of the terminal in columns.
+///
+/// Asks the terminal attached to stdout first, then falls back to `$COLUMNS`,
+/// `None` when neither is available, e.g. when piping into a file.
+pub fn width() -> Option<usize> {
+    ioctl_width().or_else(|| {
+        std::env::var("COLUMNS")
+            .ok()
+            .and_then(|columns| columns.trim().parse().ok())
+            .filter(|columns| *columns > 0)
+    })
+}
+
+#[cfg(unix)]
+fn ioctl_width() -> Option<usize> {
+    let mut size = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: TIOCGWINSZ only writes into the winsize struct we pass
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    (result == 0 && size.ws_col > 0).then_some(size.ws_col as usize)
+}
+
+#[cfg(not(unix))]
+fn ioctl_width() -> Option<usize> {
+    None
+}
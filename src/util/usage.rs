@@ -0,0 +1,125 @@
+//! inline usage bars and threshold coloring for resource lines
+use colored::*;
+use serde_derive::Deserialize;
+
+/// store parsed information from the "\[usage\]" table
+///
+/// ```toml
+/// [usage]
+/// # Optional: bar = true
+/// # Optional: bar_width = 10
+/// # Optional: bar_filled = "█"
+/// # Optional: bar_empty = "░"
+/// # Optional: colored = true
+/// # Optional: warning = 60
+/// # Optional: critical = 85
+/// # Optional: cpu = true
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct UsageConfig {
+    /// append a bar graph to every resource line
+    pub bar: Option<bool>,
+
+    /// number of cells in the bar, defaults to 10
+    pub bar_width: Option<usize>,
+
+    /// character used for the used part of the bar, defaults to `█`
+    pub bar_filled: Option<String>,
+
+    /// character used for the free part of the bar, defaults to `░`
+    pub bar_empty: Option<String>,
+
+    /// color values green/yellow/red depending on the thresholds
+    pub colored: Option<bool>,
+
+    /// percentage from which values are shown in yellow, defaults to 60
+    pub warning: Option<u64>,
+
+    /// percentage from which values are shown in red, defaults to 85
+    pub critical: Option<u64>,
+
+    /// sample CPU usage, this delays the output by ~200ms
+    pub cpu: Option<bool>,
+}
+
+impl UsageConfig {
+    /// Formats a resource value, optionally colored and followed by a bar.
+    ///
+    /// # Arguments
+    /// * `percent` - how much of the resource is in use
+    /// * `value` - the already formatted value, e.g. `5850MB / 8192MB`
+    ///
+    /// # Returns
+    /// * `String` - the decorated value
+    ///
+    /// ```
+    /// use symfetch::util::usage::UsageConfig;
+    ///
+    /// let usage = UsageConfig {
+    ///     bar: Some(true),
+    ///     bar_width: Some(4),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(usage.format(50, "1GB / 2GB"), "1GB / 2GB [██░░]");
+    /// ```
+    pub fn format(&self, percent: u64, value: &str) -> String {
+        self.decorate(percent, percent, value)
+    }
+
+    /// Like [`format`](Self::format) for levels that are critical when low, e.g. a battery
+    /// charge. The bar shows `level`, the thresholds apply to what is missing.
+    ///
+    /// ```
+    /// use symfetch::util::usage::UsageConfig;
+    ///
+    /// let usage = UsageConfig {
+    ///     bar: Some(true),
+    ///     bar_width: Some(4),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(usage.format_level(75, "75%"), "75% [███░]");
+    /// ```
+    pub fn format_level(&self, level: u64, value: &str) -> String {
+        self.decorate(level, 100 - level.min(100), value)
+    }
+
+    fn decorate(&self, fill: u64, severity: u64, value: &str) -> String {
+        let value = self.colorize(severity, value);
+        if self.bar.unwrap_or(false) {
+            format!("{value} {}", self.bar(fill, severity))
+        } else {
+            value
+        }
+    }
+
+    /// Returns a bar graph such as `[██████░░░░]` filled to `fill` percent and colored
+    /// by the `severity` percentage.
+    pub fn bar(&self, fill: u64, severity: u64) -> String {
+        let width = self.bar_width.unwrap_or(10);
+        let filled_char = self.bar_filled.as_deref().unwrap_or("█");
+        let empty_char = self.bar_empty.as_deref().unwrap_or("░");
+
+        let filled = ((fill.min(100) as usize * width) + 50) / 100;
+        let bar = format!(
+            "{}{}",
+            filled_char.repeat(filled),
+            empty_char.repeat(width - filled)
+        );
+        format!("[{}]", self.colorize(severity, &bar))
+    }
+
+    /// Colors `text` green, yellow or red depending on the configured thresholds.
+    pub fn colorize(&self, percent: u64, text: &str) -> String {
+        if !self.colored.unwrap_or(false) {
+            return text.to_string();
+        }
+
+        if percent >= self.critical.unwrap_or(85) {
+            text.red().to_string()
+        } else if percent >= self.warning.unwrap_or(60) {
+            text.yellow().to_string()
+        } else {
+            text.green().to_string()
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! redrawing the output in place while refreshing the dynamic modules
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use sysinfo::System;
+
+use crate::data::{Data, Logo};
+use crate::system_info::SystemInfo;
+use crate::util::term;
+
+/// seconds between refreshes when `--watch` is given without a value
+pub const DEFAULT_INTERVAL: &str = "2";
+
+/// how often keys and signals are checked for
+const TICK: Duration = Duration::from_millis(100);
+
+/// set by SIGTERM, SIGHUP and SIGINT, which would otherwise skip restoring the terminal
+static TERMINATED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn terminate(_: libc::c_int) {
+    TERMINATED.store(true, Ordering::Relaxed);
+}
+
+/// puts the terminal back the way it was, also when drawing fails or panics
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        #[cfg(unix)]
+        for signal in [libc::SIGTERM, libc::SIGHUP, libc::SIGINT] {
+            // SAFETY: the handler only stores to an atomic, which is async-signal-safe
+            unsafe {
+                libc::signal(signal, terminate as *const () as libc::sighandler_t);
+            }
+        }
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Shows the output on the alternate screen and refreshes it every `interval` until
+/// `q`, `Esc` or `Ctrl-C` is pressed.
+///
+/// Memory, load, battery, network, storage and uptime are probed again on every refresh,
+/// the logo and all other modules are only gathered once. Resizing the terminal lays
+/// the output out again right away.
+///
+/// Refreshing happens on a thread of its own, a slow probe doesn't delay quitting.
+/// SIGTERM, SIGHUP and SIGINT restore the terminal before returning as well.
+pub fn run(data: &Data, interval: Duration) -> io::Result<()> {
+    let logo = data.logo();
+    let mut system_info = SystemInfo::new(&data.config);
+    let refreshes = spawn_refresher(system_info.clone(), interval);
+
+    let _screen = Screen::enter()?;
+    draw(data, &logo, &system_info)?;
+    while !TERMINATED.load(Ordering::Relaxed) {
+        // signals interrupt the wait for input
+        let ready = match event::poll(TICK) {
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            ready => ready?,
+        };
+        if ready {
+            match event::read()? {
+                Event::Key(key) if quits(&key) => return Ok(()),
+                Event::Resize(..) => draw(data, &logo, &system_info)?,
+                _ => {}
+            }
+        }
+        if let Some(refreshed) = refreshes.try_iter().last() {
+            system_info = refreshed;
+            draw(data, &logo, &system_info)?;
+        }
+    }
+    Ok(())
+}
+
+/// Refreshes a copy of `system_info` every `interval` and sends it back, until the
+/// receiver is dropped.
+fn spawn_refresher(mut system_info: SystemInfo, interval: Duration) -> mpsc::Receiver<SystemInfo> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut sys = System::new();
+        // CPU usage is measured between two refreshes
+        sys.refresh_cpu_usage();
+        let mut refreshed = Instant::now();
+        loop {
+            thread::sleep(interval.saturating_sub(refreshed.elapsed()));
+            system_info.refresh(&mut sys, refreshed.elapsed());
+            refreshed = Instant::now();
+            if sender.send(system_info.clone()).is_err() {
+                return;
+            }
+        }
+    });
+    receiver
+}
+
+fn quits(key: &KeyEvent) -> bool {
+    key.kind == KeyEventKind::Press
+        && match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => true,
+            KeyCode::Char('c') => key.modifiers.contains(KeyModifiers::CONTROL),
+            _ => false,
+        }
+}
+
+/// Overwrites the screen line by line, clearing only what is left over to avoid flicker.
+fn draw(data: &Data, logo: &Logo, system_info: &SystemInfo) -> io::Result<()> {
+    let frame = data.arrange(logo, system_info, term::width(), false);
+    let mut stdout = io::stdout().lock();
+    queue!(stdout, MoveTo(0, 0))?;
+    for (i, line) in frame.lines.iter().enumerate() {
+        // raw mode doesn't turn `\n` into a carriage return, and a newline after the
+        // last line would scroll a screen that is exactly filled
+        if i > 0 {
+            write!(stdout, "\r\n")?;
+        }
+        write!(stdout, "{line}")?;
+        queue!(stdout, Clear(ClearType::UntilNewLine))?;
+    }
+    queue!(stdout, Clear(ClearType::FromCursorDown))?;
+    stdout.flush()
+}
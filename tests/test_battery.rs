@@ -0,0 +1,18 @@
+use std::path::Path;
+
+use symfetch::modules::battery::probe;
+
+#[test]
+fn test_battery_sysfs_probe() {
+    let power = probe(Path::new("tests/fixtures/root"));
+
+    assert_eq!(power.ac_online, Some(false));
+    assert_eq!(power.batteries.len(), 1);
+
+    let battery = &power.batteries[0];
+    assert_eq!(battery.name, "BAT0");
+    assert_eq!(battery.capacity, 80);
+    assert_eq!(battery.status, "Discharging");
+    assert_eq!(battery.minutes_left, Some(240));
+    assert_eq!(battery.health, Some(90));
+}
@@ -0,0 +1,17 @@
+use std::path::Path;
+
+use symfetch::modules::cpu::probe;
+use sysinfo::System;
+
+#[test]
+fn test_cpu_sysfs_probe() {
+    let cpu = probe(&System::new(), Path::new("tests/fixtures/root"));
+
+    assert_eq!(cpu.threads, 6);
+    assert_eq!(cpu.cores, 4);
+    assert_eq!(cpu.performance_cores, Some(2));
+    assert_eq!(cpu.efficiency_cores, Some(2));
+    assert_eq!(cpu.current_mhz, Some(2100));
+    assert_eq!(cpu.max_mhz, Some(4700));
+    assert_eq!(cpu.temperature, Some(47.0));
+}
@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use symfetch::modules::disk::{DiskConfig, DiskEntry, filter};
+
+fn entry(device: &str, mount: &str, fs: &str) -> DiskEntry {
+    DiskEntry {
+        device: device.to_string(),
+        mount_point: PathBuf::from(mount),
+        fs_type: fs.to_string(),
+        total: 100,
+        available: 25,
+    }
+}
+
+#[test]
+fn test_disk_filter_rules() {
+    let entries = vec![
+        entry("/dev/sda1", "/", "ext4"),
+        entry("/dev/sda2", "/boot/efi", "vfat"),
+        entry("/dev/sdb1", "/mnt/backup", "xfs"),
+        entry("/dev/loop0", "/snap/core/1", "squashfs"),
+    ];
+    let config = DiskConfig {
+        exclude: Some(vec!["/boot*".to_string()]),
+        exclude_fs: Some(vec!["xfs".to_string()]),
+        ..Default::default()
+    };
+
+    let disks = filter(entries, &config);
+
+    assert_eq!(disks.len(), 1);
+    assert_eq!(disks[0].mount_point, PathBuf::from("/"));
+    assert_eq!(disks[0].percent(), 75);
+}
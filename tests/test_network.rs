@@ -0,0 +1,35 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use symfetch::modules::network::{LinkKind, interfaces};
+
+#[test]
+fn test_network_sysfs_interfaces() {
+    let addresses: BTreeMap<String, Vec<String>> = [
+        ("lo", "127.0.0.1/8"),
+        ("eth0", "10.0.0.2/24"),
+        ("wlan0", "192.168.1.20/24"),
+        ("wg0", "10.8.0.3/32"),
+        ("docker0", "172.17.0.1/16"),
+    ]
+    .into_iter()
+    .map(|(name, address)| (name.to_string(), vec![address.to_string()]))
+    .collect();
+
+    let interfaces = interfaces(Path::new("tests/fixtures/root"), &addresses);
+    let names: Vec<&str> = interfaces.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(names, vec!["eth0", "wg0", "wlan0"]);
+
+    assert_eq!(interfaces[0].kind, LinkKind::Wired);
+    assert_eq!(interfaces[0].speed, Some(1000));
+    assert_eq!(interfaces[0].counters, Some((1048576, 4096)));
+    assert_eq!(interfaces[1].counters, None);
+    assert_eq!(interfaces[1].kind, LinkKind::Tunnel);
+    assert_eq!(
+        interfaces[2].kind,
+        LinkKind::Wireless {
+            ssid: None,
+            signal: Some(-40)
+        }
+    );
+}
@@ -0,0 +1,70 @@
+use std::fs;
+use std::io::Cursor;
+
+use symfetch::util::sqlite::count_rows;
+
+const RPMDB: &str = "tests/fixtures/root/var/lib/rpm/rpmdb.sqlite";
+/// 512 byte pages, every row and the schema of `Packages` continue on overflow pages
+const OVERFLOW: &str = "tests/fixtures/sqlite/overflow.sqlite";
+
+/// databases a crashed or interrupted rpm can leave behind
+fn corrupt_dbs() -> Vec<(&'static str, Vec<u8>)> {
+    let valid = fs::read(RPMDB).unwrap();
+
+    let magic_only = b"SQLite format 3\0".to_vec();
+
+    let mut zero_page_size = valid.clone();
+    zero_page_size[16..18].copy_from_slice(&[0, 0]);
+
+    // turn page 1 into an interior page whose right-most child is page 0
+    let mut zero_child = valid.clone();
+    zero_child[100] = 0x05;
+    zero_child[103..105].copy_from_slice(&[0, 0]);
+    zero_child[108..112].copy_from_slice(&[0, 0, 0, 0]);
+
+    // ... or page 1 itself
+    let mut cycle = zero_child.clone();
+    cycle[108..112].copy_from_slice(&[0, 0, 0, 1]);
+
+    // the schema survived but the table it points to didn't
+    let truncated = valid[..4096].to_vec();
+
+    // so many reserved bytes that no cell fits on a page
+    let mut reserved = fs::read(OVERFLOW).unwrap();
+    reserved[20] = 255;
+
+    // an unknown page type
+    let mut page_type = valid.clone();
+    page_type[100] = 0x42;
+
+    vec![
+        ("magic_only", magic_only),
+        ("zero_page_size", zero_page_size),
+        ("zero_child", zero_child),
+        ("cycle", cycle),
+        ("truncated", truncated),
+        ("reserved", reserved),
+        ("page_type", page_type),
+    ]
+}
+
+#[test]
+fn test_sqlite_count_rows() {
+    let db = fs::read(RPMDB).unwrap();
+    assert_eq!(count_rows(Cursor::new(&db), "Packages"), Some(3));
+    assert_eq!(count_rows(Cursor::new(&db), "Missing"), None);
+}
+
+#[test]
+fn test_sqlite_overflow_pages() {
+    let db = fs::read(OVERFLOW).unwrap();
+    assert_eq!(count_rows(Cursor::new(&db), "Packages"), Some(40));
+    assert_eq!(count_rows(Cursor::new(&db), "Other"), Some(1));
+}
+
+#[test]
+fn test_sqlite_corrupt_pages() {
+    for (name, db) in corrupt_dbs() {
+        assert_eq!(count_rows(Cursor::new(&db), "Packages"), None, "{name}");
+    }
+}